@@ -0,0 +1,145 @@
+use turtle_wa::results::ResultsFormat;
+
+pub const USAGE: &str = "Usage: turtle_wa <command> [files...]
+
+Commands:
+  parse     print the triples of the given files
+  validate  check the given files and only report errors
+  stats     print triple counts of the given files
+  convert   write the triples of the given files in another format
+  query     run a sparql SELECT or ASK query over the triples of the given files
+  serve     answer sparql queries over the given files at http://127.0.0.1:<port>/sparql
+            and read and write graphs at /data
+
+Options:
+  --to <format>       output format of convert: ntriples (default) or turtle
+  --query <sparql>    the query to run, required by query
+  --results <format>  results format of query: json (default), csv or tsv
+  --port <port>       port of serve, 3030 by default
+
+Files:
+  Any number of turtle files, `-` or no file at all reads from stdin.
+
+Exit codes:
+  0  success
+  1  the input contains errors
+  2  invalid command line arguments
+  3  an input couldn't be read";
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Command {
+    Parse,
+    Validate,
+    Stats,
+    Convert(Format),
+    Query(String, ResultsFormat),
+    Serve(u16),
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Format {
+    NTriples,
+    Turtle,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "ntriples" | "nt" => Some(Format::NTriples),
+            "turtle" | "ttl" => Some(Format::Turtle),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Args {
+    Help,
+    Run(Command, Vec<String>),
+}
+
+// args without the program name
+pub fn parse_args(args: &[String]) -> Result<Args, String> {
+    let (command, files) = match args.split_first() {
+        Some((command, files)) => (command, files),
+        None => return Err("missing command".to_string()),
+    };
+
+    let command = match command.as_str() {
+        "-h" | "--help" | "help" => return Ok(Args::Help),
+        "parse" => Command::Parse,
+        "validate" => Command::Validate,
+        "stats" => Command::Stats,
+        "convert" => Command::Convert(Format::NTriples),
+        "query" => Command::Query(String::new(), ResultsFormat::Json),
+        "serve" => Command::Serve(3030),
+        command => return Err(format!("unknown command `{}`", command)),
+    };
+
+    let mut command = command;
+    let mut inputs = Vec::new();
+    let mut files = files.iter();
+
+    while let Some(file) = files.next() {
+        match file.as_str() {
+            "-h" | "--help" => return Ok(Args::Help),
+            "--to" => {
+                if !matches!(command, Command::Convert(_)) {
+                    return Err("--to is only supported by convert".to_string());
+                }
+
+                let format = files.next().ok_or("missing format after --to")?;
+
+                match Format::from_name(format) {
+                    Some(format) => command = Command::Convert(format),
+                    None => return Err(format!("unknown format `{}`", format)),
+                }
+            }
+            "--query" | "--results" => {
+                let (query, results) = match &mut command {
+                    Command::Query(query, results) => (query, results),
+                    _ => return Err(format!("{} is only supported by query", file)),
+                };
+
+                let value = files
+                    .next()
+                    .ok_or_else(|| format!("missing value after {}", file))?;
+
+                match file.as_str() {
+                    "--query" => *query = value.to_string(),
+                    _ => match ResultsFormat::from_name(value) {
+                        Some(format) => *results = format,
+                        None => return Err(format!("unknown results format `{}`", value)),
+                    },
+                }
+            }
+            "--port" => {
+                if !matches!(command, Command::Serve(_)) {
+                    return Err("--port is only supported by serve".to_string());
+                }
+
+                let port = files.next().ok_or("missing port after --port")?;
+
+                match port.parse() {
+                    Ok(port) => command = Command::Serve(port),
+                    Err(_) => return Err(format!("invalid port `{}`", port)),
+                }
+            }
+            "-" => inputs.push(file.to_string()),
+            option if option.starts_with('-') => {
+                return Err(format!("unknown option `{}`", option))
+            }
+            file => inputs.push(file.to_string()),
+        }
+    }
+
+    if matches!(&command, Command::Query(query, _) if query.is_empty()) {
+        return Err("missing --query".to_string());
+    }
+
+    if inputs.is_empty() {
+        inputs.push("-".to_string());
+    }
+
+    Ok(Args::Run(command, inputs))
+}
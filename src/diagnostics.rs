@@ -0,0 +1,24 @@
+use crate::lexer::Span;
+use crate::parser::ParseError;
+
+// a problem found while lexing or parsing, pointing at the offending token
+#[derive(PartialEq, Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn error(message: &str, span: Span) -> Diagnostic {
+        Diagnostic {
+            message: message.to_string(),
+            span,
+        }
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(error: &ParseError) -> Self {
+        Diagnostic::error(&error.message(), error.span())
+    }
+}
@@ -0,0 +1,189 @@
+// an in-memory set of triples with interned terms and spo, pos and osp indexes
+use std::collections::{BTreeSet, HashMap};
+
+use crate::parser::TripleSink;
+use crate::term::*;
+
+// ids of interned terms, in the order of the index they are stored in
+type Key = (usize, usize, usize);
+
+#[derive(Clone, Copy)]
+enum Order {
+    Spo,
+    Pos,
+    Osp,
+}
+
+impl Order {
+    // the key as subject, predicate and object ids
+    fn spo(self, (a, b, c): Key) -> Key {
+        match self {
+            Order::Spo => (a, b, c),
+            Order::Pos => (c, a, b),
+            Order::Osp => (b, c, a),
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct Graph {
+    terms: Vec<Term>,
+    ids: HashMap<Term, usize>,
+    spo: BTreeSet<Key>,
+    pos: BTreeSet<Key>,
+    osp: BTreeSet<Key>,
+}
+
+impl Graph {
+    pub fn new() -> Graph {
+        Graph::default()
+    }
+
+    // returns false if the graph already contained the triple
+    pub fn insert(&mut self, triple: Triple) -> bool {
+        let s = self.intern(Term::from(triple.subject));
+        let p = self.intern(Term::from(triple.predicate));
+        let o = self.intern(triple.object);
+
+        if !self.spo.insert((s, p, o)) {
+            return false;
+        }
+
+        self.pos.insert((p, o, s));
+        self.osp.insert((o, s, p));
+
+        true
+    }
+
+    pub fn contains(&self, triple: &Triple) -> bool {
+        self.triples_matching(
+            Some(&triple.subject),
+            Some(&triple.predicate),
+            Some(&triple.object),
+        )
+        .next()
+        .is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.spo.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spo.is_empty()
+    }
+
+    // all triples in spo order, terms sort in the order they were first inserted
+    pub fn iter(&self) -> impl Iterator<Item = Triple> + '_ {
+        self.triples_matching(None, None, None)
+    }
+
+    // the triples that match a pattern, None matches any term
+    pub fn triples_matching<'a>(
+        &'a self,
+        subject: Option<&Subject>,
+        predicate: Option<&NamedNode>,
+        object: Option<&Term>,
+    ) -> impl Iterator<Item = Triple> + 'a {
+        self.keys_matching(subject, predicate, object)
+            .map(|key| self.triple(key))
+    }
+
+    // counts the matches of a pattern without building their triples
+    pub fn count_matching(
+        &self,
+        subject: Option<&Subject>,
+        predicate: Option<&NamedNode>,
+        object: Option<&Term>,
+    ) -> usize {
+        self.keys_matching(subject, predicate, object).count()
+    }
+
+    // the spo keys of the matching triples, read from the index whose key starts with the bound terms
+    fn keys_matching<'a>(
+        &'a self,
+        subject: Option<&Subject>,
+        predicate: Option<&NamedNode>,
+        object: Option<&Term>,
+    ) -> impl Iterator<Item = Key> + 'a {
+        let s = subject.map(|subject| self.id(&Term::from(subject.clone())));
+        let p = predicate.map(|predicate| self.id(&Term::from(predicate.clone())));
+        let o = object.map(|object| self.id(object));
+        // a term that was never inserted can't match anything
+        let unknown = [s, p, o].contains(&Some(None));
+        let (s, p, o) = (s.flatten(), p.flatten(), o.flatten());
+
+        let (index, order, key) = match (s, p, o) {
+            (Some(_), None, Some(_)) => (&self.osp, Order::Osp, [o, s, None]),
+            (Some(_), ..) | (None, None, None) => (&self.spo, Order::Spo, [s, p, o]),
+            (None, Some(_), _) => (&self.pos, Order::Pos, [p, o, None]),
+            (None, None, Some(_)) => (&self.osp, Order::Osp, [o, None, None]),
+        };
+
+        let bound = |default: usize| match unknown {
+            true => (usize::MAX, usize::MAX, usize::MAX),
+            false => (
+                key[0].unwrap_or(default),
+                key[1].unwrap_or(default),
+                key[2].unwrap_or(default),
+            ),
+        };
+
+        index
+            .range(bound(0)..=bound(usize::MAX))
+            .map(move |key| order.spo(*key))
+    }
+
+    fn intern(&mut self, term: Term) -> usize {
+        if let Some(id) = self.ids.get(&term) {
+            return *id;
+        }
+
+        self.terms.push(term.clone());
+        self.ids.insert(term, self.terms.len() - 1);
+
+        self.terms.len() - 1
+    }
+
+    fn id(&self, term: &Term) -> Option<usize> {
+        self.ids.get(term).copied()
+    }
+
+    fn triple(&self, (s, p, o): Key) -> Triple {
+        let subject = Subject::try_from(self.terms[s].clone()).expect("subject term");
+        let predicate = match &self.terms[p] {
+            Term::NamedNode(predicate) => predicate.clone(),
+            term => panic!("expected a predicate, got {:?}", term),
+        };
+
+        Triple {
+            subject,
+            predicate,
+            object: self.terms[o].clone(),
+        }
+    }
+}
+
+impl Extend<Triple> for Graph {
+    fn extend<I: IntoIterator<Item = Triple>>(&mut self, triples: I) {
+        for triple in triples {
+            self.insert(triple);
+        }
+    }
+}
+
+impl FromIterator<Triple> for Graph {
+    fn from_iter<I: IntoIterator<Item = Triple>>(triples: I) -> Self {
+        let mut graph = Graph::new();
+
+        graph.extend(triples);
+        graph
+    }
+}
+
+// lets the parser load documents straight into a graph
+impl TripleSink for Graph {
+    fn triple(&mut self, triple: Triple) {
+        self.insert(triple);
+    }
+}
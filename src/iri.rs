@@ -0,0 +1,187 @@
+// iri references and their resolution against a base, see RFC 3986 section 5.2
+
+// the parts of an iri reference, `scheme://authority/path?query#fragment`
+#[derive(PartialEq, Debug)]
+struct Reference<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> Reference<'a> {
+    fn parse(iri: &'a str) -> Reference<'a> {
+        let (rest, fragment) = match iri.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (iri, None),
+        };
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+        let (scheme, rest) = match scheme_length(rest) {
+            Some(length) => (Some(&rest[..length]), &rest[length + 1..]),
+            None => (None, rest),
+        };
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(rest) => {
+                let end = rest.find('/').unwrap_or(rest.len());
+
+                (Some(&rest[..end]), &rest[end..])
+            }
+            None => (None, rest),
+        };
+
+        Reference {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        }
+    }
+}
+
+// length of the scheme, `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )` followed by `:`
+fn scheme_length(iri: &str) -> Option<usize> {
+    let colon = iri.find(':')?;
+    let mut scheme = iri[..colon].chars();
+
+    match scheme.next() {
+        Some(c) if c.is_ascii_alphabetic() => scheme
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+            .then_some(colon),
+        _ => None,
+    }
+}
+
+pub fn is_absolute(iri: &str) -> bool {
+    scheme_length(iri).is_some()
+}
+
+// resolves a relative reference against an absolute base iri
+pub fn resolve(base: &str, reference: &str) -> String {
+    let base = Reference::parse(base);
+    let reference = Reference::parse(reference);
+
+    let (scheme, authority, path, query) = match reference {
+        Reference {
+            scheme: Some(scheme),
+            ..
+        } => (
+            Some(scheme),
+            reference.authority,
+            remove_dot_segments(reference.path),
+            reference.query,
+        ),
+        Reference {
+            authority: Some(authority),
+            ..
+        } => (
+            base.scheme,
+            Some(authority),
+            remove_dot_segments(reference.path),
+            reference.query,
+        ),
+        Reference { path: "", .. } => (
+            base.scheme,
+            base.authority,
+            base.path.to_string(),
+            reference.query.or(base.query),
+        ),
+        Reference { path, .. } if path.starts_with('/') => (
+            base.scheme,
+            base.authority,
+            remove_dot_segments(path),
+            reference.query,
+        ),
+        Reference { path, .. } => (
+            base.scheme,
+            base.authority,
+            remove_dot_segments(&merge(&base, path)),
+            reference.query,
+        ),
+    };
+
+    let mut iri = String::new();
+
+    if let Some(scheme) = scheme {
+        iri.push_str(scheme);
+        iri.push(':');
+    }
+
+    if let Some(authority) = authority {
+        iri.push_str("//");
+        iri.push_str(authority);
+    }
+
+    iri.push_str(&path);
+
+    if let Some(query) = query {
+        iri.push('?');
+        iri.push_str(query);
+    }
+
+    if let Some(fragment) = reference.fragment {
+        iri.push('#');
+        iri.push_str(fragment);
+    }
+
+    iri
+}
+
+// appends a relative path to the directory of the base path
+fn merge(base: &Reference, path: &str) -> String {
+    if base.authority.is_some() && base.path.is_empty() {
+        return format!("/{}", path);
+    }
+
+    match base.path.rfind('/') {
+        Some(end) => format!("{}{}", &base.path[..=end], path),
+        None => path.to_string(),
+    }
+}
+
+// removes `.` and `..` segments, see RFC 3986 section 5.2.4
+fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output = String::new();
+
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") {
+            input = &input[3..];
+            remove_last_segment(&mut output);
+        } else if input == "/.." {
+            input = "/";
+            remove_last_segment(&mut output);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            // move the first segment, including its leading `/`, to the output
+            let end = match input.strip_prefix('/') {
+                Some(rest) => rest.find('/').map_or(input.len(), |end| end + 1),
+                None => input.find('/').unwrap_or(input.len()),
+            };
+
+            output.push_str(&input[..end]);
+            input = &input[end..];
+        }
+    }
+
+    output
+}
+
+fn remove_last_segment(output: &mut String) {
+    let end = output.rfind('/').unwrap_or(0);
+
+    output.truncate(end);
+}
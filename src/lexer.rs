@@ -1,31 +1,106 @@
 // here we go again
 
+use crate::diagnostics::Diagnostic;
 use std::{
-    iter::{Enumerate, Peekable},
-    str::Chars,
+    fmt,
+    io::{self, BufRead},
 };
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum Lexeme {
-    Iri(String),                     // <http://example.com>
-    PrefixedIri(String),             // ex:foo
-    A,                               // a -> rdf:type
-    Prefix(String, String),          // @prefix | PREFIX
-    Base(String),                    // @base | BASE
-    Literal(String),                 // "literal"
-    LangLiteral(String, String),     // "literal"@en
-    DataTypeLiteral(String, String), // "literal"^^<iri>
-    MultilineLiteral(String),        // """literal"""
-    EndToken,                        // .
-    PredicateListToken,              // ;
-    ObjectListToken,                 // ,
-    Comment(String),                 // # comment
-    Unknown(String),                 // unknown token
+    Iri(String),                             // <http://example.com>
+    PrefixedIri(String),                     // ex:foo
+    BlankNodeLabel(String),                  // _:b0
+    A,                                       // a -> rdf:type
+    Prefix(String, String),                  // @prefix | PREFIX
+    Base(String),                            // @base | BASE
+    Literal(String),                         // "literal"
+    LangLiteral(String, String),             // "literal"@en
+    DataTypeLiteral(String, String),         // "literal"^^<iri>
+    PrefixedDataTypeLiteral(String, String), // "literal"^^ex:type
+    MultilineLiteral(String),                // """literal"""
+    EndToken,                                // .
+    PredicateListToken,                      // ;
+    ObjectListToken,                         // ,
+    BlankNodeStartToken,                     // [
+    BlankNodeEndToken,                       // ]
+    CollectionStartToken,                    // (
+    CollectionEndToken,                      // )
+    GraphStartToken,                         // {
+    GraphEndToken,                           // }
+    Graph,                                   // GRAPH
+    Variable(String),                        // ?name, only in query patterns
+    Operator(String),                        // = != < <= > >= ! && || *, only in queries
+    Comment(String),                         // # comment
+    Unknown(String),                         // unknown token
+}
+
+impl fmt::Display for Lexeme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lexeme::Iri(iri) => write!(f, "<{}>", iri),
+            Lexeme::PrefixedIri(iri) => write!(f, "{}", iri),
+            Lexeme::BlankNodeLabel(label) => write!(f, "_:{}", label),
+            Lexeme::A => write!(f, "a"),
+            Lexeme::Prefix(prefix, iri) => write!(f, "@prefix {} <{}>", prefix, iri),
+            Lexeme::Base(iri) => write!(f, "@base <{}>", iri),
+            Lexeme::Literal(literal) => write!(f, "\"{}\"", literal),
+            Lexeme::LangLiteral(literal, lang) => write!(f, "\"{}\"@{}", literal, lang),
+            Lexeme::DataTypeLiteral(literal, iri) => write!(f, "\"{}\"^^<{}>", literal, iri),
+            Lexeme::PrefixedDataTypeLiteral(literal, iri) => {
+                write!(f, "\"{}\"^^{}", literal, iri)
+            }
+            Lexeme::MultilineLiteral(literal) => write!(f, "\"\"\"{}\"\"\"", literal),
+            Lexeme::EndToken => write!(f, "."),
+            Lexeme::PredicateListToken => write!(f, ";"),
+            Lexeme::ObjectListToken => write!(f, ","),
+            Lexeme::BlankNodeStartToken => write!(f, "["),
+            Lexeme::BlankNodeEndToken => write!(f, "]"),
+            Lexeme::CollectionStartToken => write!(f, "("),
+            Lexeme::CollectionEndToken => write!(f, ")"),
+            Lexeme::GraphStartToken => write!(f, "{{"),
+            Lexeme::GraphEndToken => write!(f, "}}"),
+            Lexeme::Graph => write!(f, "GRAPH"),
+            Lexeme::Variable(name) => write!(f, "?{}", name),
+            Lexeme::Operator(operator) => write!(f, "{}", operator),
+            Lexeme::Comment(comment) => write!(f, "#{}", comment),
+            Lexeme::Unknown(token) => write!(f, "{}", token),
+        }
+    }
+}
+
+// position of a token, columns count characters and are zero based
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct Span {
+    pub line: u32,
+    pub start: usize,  // column of the first character
+    pub end: usize,    // column after the last character
+    pub offset: usize, // byte offset of the first character in the tokenized input
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Token {
+    pub lexeme: Lexeme,
+    pub span: Span,
+}
+
+impl From<Lexeme> for Token {
+    fn from(lexeme: Lexeme) -> Self {
+        Token {
+            lexeme,
+            span: Span::default(),
+        }
+    }
 }
 
 pub struct LexerContext {
     pub parse_multiline: bool,
     pub parsed_multilines: Vec<Lexeme>,
+    pub multiline_span: Span, // where the currently parsed multiline literal started
+    pub multiline_quote: char, // `"` or `'`
+    pub invalid_escape: bool, // the literal or iri being read has an escape that couldn't be decoded
+    pub query: bool,          // lexing sparql, where a `<` that doesn't start an iri is an operator
+    pub diagnostics: Vec<Diagnostic>, // problems with input that couldn't be turned into tokens
 }
 
 impl LexerContext {
@@ -33,16 +108,157 @@ impl LexerContext {
         LexerContext {
             parse_multiline: false,
             parsed_multilines: Vec::new(),
+            multiline_span: Span::default(),
+            multiline_quote: '"',
+            invalid_escape: false,
+            query: false,
+            diagnostics: Vec::new(),
+        }
+    }
+}
+
+impl Default for LexerContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the characters of a reader, keeping track of the position in the input
+struct Cursor<R> {
+    reader: R,
+    buffer: String,  // the part of the input that is read, a line at a time
+    position: usize, // byte position of the next character in the buffer
+    line: u32,
+    column: usize,
+    offset: usize,
+    line_end: Option<usize>, // column of the first line break after `mark`
+    error: Option<io::Error>,
+}
+
+impl<R: BufRead> Cursor<R> {
+    fn new(reader: R, line: u32) -> Cursor<R> {
+        Cursor {
+            reader,
+            buffer: String::new(),
+            position: 0,
+            line,
+            column: 0,
+            offset: 0,
+            line_end: None,
+            error: None,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        if self.position == self.buffer.len() && self.error.is_none() {
+            self.buffer.clear();
+            self.position = 0;
+
+            if let Err(error) = self.reader.read_line(&mut self.buffer) {
+                self.error = Some(error);
+            }
+        }
+
+        self.buffer[self.position..].chars().next()
+    }
+
+    // looks further ahead within the current line, `peek_nth(0)` is `peek()`
+    fn peek_nth(&mut self, n: usize) -> Option<char> {
+        self.peek()?;
+        self.buffer[self.position..].chars().nth(n)
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+
+        self.position += c.len_utf8();
+        self.offset += c.len_utf8();
+
+        match c {
+            '\n' => {
+                self.line_end.get_or_insert(self.column);
+                self.line += 1;
+                self.column = 0;
+            }
+            _ => self.column += 1,
+        }
+
+        Some(c)
+    }
+
+    fn mark(&mut self) {
+        self.line_end = None;
+    }
+
+    // what is left of the current line, from the next character on
+    fn rest_of_line(&mut self) -> &str {
+        self.peek();
+        &self.buffer[self.position..]
+    }
+}
+
+// lexes a whole input as one stream of characters, lines are tracked internally
+pub struct Lexer<R> {
+    input: Cursor<R>,
+    context: LexerContext,
+    finished: bool,
+}
+
+impl<R: BufRead> Lexer<R> {
+    pub fn new(reader: R) -> Lexer<R> {
+        Lexer {
+            input: Cursor::new(reader, 1),
+            context: LexerContext::new(),
+            finished: false,
         }
     }
+
+    // problems with input that couldn't be turned into tokens, found so far
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.context.diagnostics)
+    }
+}
+
+impl<'a> From<&'a str> for Lexer<&'a [u8]> {
+    fn from(text: &'a str) -> Self {
+        Lexer::new(text.as_bytes())
+    }
 }
 
-pub fn tokenize(line: &str, line_num: u32, context: &mut LexerContext) -> Vec<Lexeme> {
+impl<R: BufRead> Iterator for Lexer<R> {
+    type Item = io::Result<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let token = next_token(&mut self.input, &mut self.context);
+
+        if let Some(error) = self.input.error.take() {
+            self.finished = true;
+            return Some(Err(error));
+        }
+
+        match token {
+            Some(token) => Some(Ok(token)),
+            None => {
+                finish(&mut self.context);
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
+
+pub fn tokenize(line: &str, line_num: u32, context: &mut LexerContext) -> Vec<Token> {
     let mut tokens = Vec::new();
-    let mut itr = line.chars().into_iter().enumerate().peekable();
+    let mut input = Cursor::new(line.as_bytes(), line_num);
 
     if context.parse_multiline {
-        match read_multiline_part(&mut itr) {
+        let quote = context.multiline_quote;
+
+        match read_multiline_part(&mut input, context, quote) {
             (multiline, true) => {
                 context
                     .parsed_multilines
@@ -58,7 +274,18 @@ pub fn tokenize(line: &str, line_num: u32, context: &mut LexerContext) -> Vec<Le
                     .collect::<Vec<String>>()
                     .join("\n");
 
-                tokens.push(Lexeme::MultilineLiteral(multiline_str));
+                let lexeme = read_literal_suffix(
+                    &mut input,
+                    context,
+                    multiline_str,
+                    Lexeme::MultilineLiteral,
+                );
+                let lexeme = reject_invalid_escape(context, lexeme);
+
+                tokens.push(Token {
+                    lexeme,
+                    span: context.multiline_span,
+                });
 
                 // reset context
                 context.parse_multiline = false;
@@ -74,298 +301,705 @@ pub fn tokenize(line: &str, line_num: u32, context: &mut LexerContext) -> Vec<Le
         };
     }
 
-    while let Some((offset, c)) = itr.peek() {
-        match c {
-            '<' => {
-                let iri = read_iri(&mut itr);
-                tokens.push(iri);
-            }
-            '"' => {
-                let result = read_literal(&mut itr, context);
-
-                match result {
-                    Some(lexeme) => tokens.push(lexeme),
-                    _ => {}
-                }
-            }
-            '@' | 'P' | 'B' => {
-                let token = read_token(&mut itr);
+    while let Some(token) = next_token(&mut input, context) {
+        tokens.push(token);
+    }
 
-                match token.as_str() {
-                    "@prefix" | "PREFIX" => {
-                        skip_whitespace(&mut itr);
+    tokens
+}
 
-                        let (token, with_colon) = read_prefix(&mut itr);
+// reports a multiline literal that is still open at the end of the input
+pub fn finish(context: &mut LexerContext) {
+    if context.parse_multiline {
+        context.diagnostics.push(Diagnostic::error(
+            &format!(
+                "unterminated multiline literal, missing `{0}{0}{0}`",
+                context.multiline_quote
+            ),
+            context.multiline_span,
+        ));
 
-                        match with_colon {
-                            true => {
-                                skip_whitespace(&mut itr);
+        context.parse_multiline = false;
+        context.parsed_multilines.clear();
+        context.invalid_escape = false;
+    }
+}
 
-                                let iri = read_iri(&mut itr);
+// reads up to the next token, problems along the way are recorded in the context
+fn next_token<R: BufRead>(input: &mut Cursor<R>, context: &mut LexerContext) -> Option<Token> {
+    loop {
+        skip_whitespace(input);
 
-                                match iri {
-                                    Lexeme::Iri(iri) => {
-                                        tokens.push(Lexeme::Prefix(token, iri));
-                                    }
-                                    _ => {
-                                        tokens.push(Lexeme::Unknown(token));
-                                    }
-                                }
-                            }
+        let c = input.peek()?;
+        let (line, start, offset) = (input.line, input.column, input.offset);
 
-                            false => tokens.push(Lexeme::Unknown(token)),
-                        }
-                    }
-                    "@base" | "BASE" => {
-                        skip_whitespace(&mut itr);
+        input.mark();
 
-                        let base_iri = read_base(&mut itr);
+        // Err is a problem that is reported instead of emitting a token
+        let lexeme: Result<Option<Lexeme>, &str> = match c {
+            '<' if context.query && !starts_iri(input.rest_of_line()) => {
+                Ok(Some(Lexeme::Operator(read_operator(input))))
+            }
+            '>' | '=' | '!' | '&' | '|' | '*' => Ok(Some(Lexeme::Operator(read_operator(input)))),
+            '<' => match read_iri(input, context) {
+                Ok(iri) => Ok(Some(reject_invalid_escape(context, Lexeme::Iri(iri)))),
+                Err(message) => Err(message),
+            },
+            '"' | '\'' => Ok(read_literal(input, context)),
+            '@' => {
+                let token = read_token(input);
 
-                        match base_iri {
-                            Some(iri) => {
-                                tokens.push(Lexeme::Base(iri));
-                            }
-                            _ => {
-                                tokens.push(Lexeme::Unknown(token));
-                            }
-                        }
-                    }
-                    _ => {
-                        tokens.push(Lexeme::Unknown(token.to_string()));
-                    }
+                match token.as_str() {
+                    "@prefix" => read_prefix_directive(input, context),
+                    "@base" => read_base_directive(input, context),
+                    _ => Ok(Some(Lexeme::Unknown(token.to_string()))),
                 }
             }
+            '.' if input.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) => {
+                Ok(Some(Lexeme::Unknown(read_number(input))))
+            }
+            '0'..='9' | '+' | '-' => Ok(Some(Lexeme::Unknown(read_number(input)))),
             '.' => {
-                tokens.push(Lexeme::EndToken);
-                itr.next();
+                input.advance();
+                Ok(Some(Lexeme::EndToken))
             }
             ';' => {
-                tokens.push(Lexeme::PredicateListToken);
-                itr.next();
+                input.advance();
+                Ok(Some(Lexeme::PredicateListToken))
             }
             ',' => {
-                tokens.push(Lexeme::ObjectListToken);
-                itr.next();
+                input.advance();
+                Ok(Some(Lexeme::ObjectListToken))
+            }
+            '[' => {
+                input.advance();
+                Ok(Some(Lexeme::BlankNodeStartToken))
+            }
+            ']' => {
+                input.advance();
+                Ok(Some(Lexeme::BlankNodeEndToken))
+            }
+            '(' => {
+                input.advance();
+                Ok(Some(Lexeme::CollectionStartToken))
+            }
+            ')' => {
+                input.advance();
+                Ok(Some(Lexeme::CollectionEndToken))
             }
-            'a' => {
-                tokens.push(Lexeme::A);
-                itr.next();
+            '{' => {
+                input.advance();
+                Ok(Some(Lexeme::GraphStartToken))
             }
-            ' ' | '\t' => {
-                itr.next();
+            '}' => {
+                input.advance();
+                Ok(Some(Lexeme::GraphEndToken))
             }
-            '#' => {
-                let comment = read_comment(&mut itr);
+            '#' => Ok(Some(read_comment(input))),
+            '?' | '$' => {
+                input.advance();
 
-                tokens.push(comment);
+                match read_variable(input) {
+                    name if name.is_empty() => Err("expected variable name"),
+                    name => Ok(Some(Lexeme::Variable(name))),
+                }
             }
-            _ => {
-                let token = read_token(&mut itr);
+            '_' if input.peek_nth(1) == Some(':') => {
+                input.advance();
+                input.advance();
 
-                match is_prefixed_uri(&token) {
-                    true => tokens.push(Lexeme::PrefixedIri(token.to_string())),
-                    false => tokens.push(Lexeme::Unknown(token.to_string())),
+                let label = read_name(input, |c| is_pn_chars_u(c) || c.is_ascii_digit());
+
+                match label.is_empty() {
+                    true => Err("expected blank node label after `_:`"),
+                    false => Ok(Some(Lexeme::BlankNodeLabel(label))),
                 }
             }
+            c if c == ':' || is_pn_chars_base(c) => {
+                let prefix = read_name(input, is_pn_chars_base);
+
+                match input.peek() {
+                    Some(':') => {
+                        input.advance();
+
+                        match read_local_name(input) {
+                            Ok(local) => {
+                                Ok(Some(Lexeme::PrefixedIri(format!("{}:{}", prefix, local))))
+                            }
+                            Err(message) => {
+                                read_token(input);
+                                Err(message)
+                            }
+                        }
+                    }
+                    _ => match prefix.as_str() {
+                        "a" => Ok(Some(Lexeme::A)),
+                        keyword if keyword.eq_ignore_ascii_case("PREFIX") => {
+                            read_prefix_directive(input, context)
+                        }
+                        keyword if keyword.eq_ignore_ascii_case("BASE") => {
+                            read_base_directive(input, context)
+                        }
+                        keyword if keyword.eq_ignore_ascii_case("GRAPH") => Ok(Some(Lexeme::Graph)),
+                        _ => Ok(Some(Lexeme::Unknown(prefix))),
+                    },
+                }
+            }
+            _ => Ok(Some(Lexeme::Unknown(read_token(input)))),
+        };
+
+        // tokens that span lines, like multiline literals, end with their first line
+        let span = Span {
+            line,
+            start,
+            end: match input.line == line {
+                true => input.column,
+                false => input.line_end.unwrap_or(input.column),
+            },
+            offset,
+        };
+
+        // multiline literals are emitted where they end, but point to where they start
+        if context.parse_multiline {
+            context.multiline_span = span;
         }
 
-        skip_whitespace(&mut itr);
+        match lexeme {
+            Ok(Some(lexeme)) => return Some(Token { lexeme, span }),
+            Ok(None) => {}
+            Err(message) => context.diagnostics.push(Diagnostic::error(message, span)),
+        }
     }
-
-    tokens
 }
 
-fn read_iri(itr: &mut Peekable<Enumerate<Chars>>) -> Lexeme {
+// Err is the problem with an iri that is missing its `<` or `>`
+fn read_iri<R: BufRead>(
+    input: &mut Cursor<R>,
+    context: &mut LexerContext,
+) -> Result<String, &'static str> {
     let mut iri = String::new();
-    let mut found_start = false;
-    let mut found_end = false;
 
-    while let Some((_, c)) = itr.peek() {
+    if input.peek() != Some('<') {
+        return Err("expected `<iri>`");
+    }
+
+    input.advance();
+
+    // the rest of an iri with an invalid character is skipped to report it once
+    let mut valid = true;
+    let mut decoded = true; // false after an escape that couldn't be decoded
+
+    while let Some(c) = input.peek() {
         match c {
-            '<' => {
-                itr.next();
-                found_start = true;
+            '>' if valid => {
+                input.advance();
+                context.invalid_escape |= !decoded;
+                return Ok(iri);
             }
             '>' => {
-                itr.next();
-                found_end = true;
-                break;
+                input.advance();
+                return Err("invalid character in iri");
+            }
+            '\\' => match read_escape(input, context, false) {
+                Some(c) => iri.push(c),
+                None => decoded = false,
+            },
+            '\n' | '\r' => break,
+            c if !is_iri_char(c) => {
+                valid = false;
+                input.advance();
             }
             _ => {
-                iri.push(*c);
-                itr.next();
+                iri.push(c);
+                input.advance();
             }
         }
     }
 
-    match (found_start, found_end) {
-        (true, true) => Lexeme::Iri(iri),
-        (_, _) => Lexeme::Unknown(iri),
-    }
+    Err("unterminated iri, missing `>`")
+}
+
+// IRIREF leaves out spaces, control characters and `<>"{}|^`\` other than in `\u` escapes
+fn is_iri_char(c: char) -> bool {
+    c > ' ' && !matches!(c, '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\')
 }
 
-fn is_prefixed_uri(token: &str) -> bool {
-    let mut colons: Vec<(usize, char)> = Vec::new();
-    let itr = token.chars().enumerate();
+// whether the text starts with an IRIREF, `<` up to its `>` on the same line
+fn starts_iri(text: &str) -> bool {
+    let mut chars = text.chars().skip(1);
 
-    for (offset, c) in itr {
-        if let ':' = c {
-            colons.push((offset, c));
+    while let Some(c) = chars.next() {
+        match c {
+            '>' => return true,
+            '\\' if matches!(chars.next(), Some('u' | 'U')) => {}
+            c if !is_iri_char(c) => return false,
+            _ => {}
         }
     }
 
-    let (first, second) = (colons.first(), colons.get(1));
+    false
+}
+
+// the rest of a `@prefix` or `PREFIX` directive, `ex: <iri>`
+fn read_prefix_directive<R: BufRead>(
+    input: &mut Cursor<R>,
+    context: &mut LexerContext,
+) -> Result<Option<Lexeme>, &'static str> {
+    skip_whitespace(input);
+
+    let prefix = read_name(input, is_pn_chars_base);
 
-    matches!((first, second), (Some((_, _)), None) if !token.ends_with(':'))
+    if input.peek() != Some(':') {
+        read_token(input);
+        return Err("expected prefix name ending with `:`");
+    }
+
+    input.advance();
+    skip_whitespace(input);
+
+    match read_iri(input, context) {
+        Ok(iri) => Ok(Some(reject_invalid_escape(
+            context,
+            Lexeme::Prefix(format!("{}:", prefix), iri),
+        ))),
+        Err(_) => Err("expected `<iri>` after prefix name"),
+    }
 }
 
-fn read_literal(
-    itr: &mut Peekable<Enumerate<Chars>>,
+// the rest of a `@base` or `BASE` directive, `<iri>`
+fn read_base_directive<R: BufRead>(
+    input: &mut Cursor<R>,
     context: &mut LexerContext,
-) -> Option<Lexeme> {
-    let literal = read_literal_value(itr);
+) -> Result<Option<Lexeme>, &'static str> {
+    skip_whitespace(input);
 
-    match literal {
-        Some(literal) => {
-            let current_char = itr.peek();
+    match read_iri(input, context) {
+        Ok(iri) => Ok(Some(reject_invalid_escape(context, Lexeme::Base(iri)))),
+        Err(_) => Err("expected `<iri>` after base"),
+    }
+}
 
-            match current_char {
-                Some((_, '@')) => {
-                    itr.next();
+// a prefix name or blank node label, `first ((PN_CHARS | '.')* PN_CHARS)?`
+// dots belong to the name only if more name characters follow them
+fn read_name<R: BufRead>(input: &mut Cursor<R>, first: fn(char) -> bool) -> String {
+    let mut name = String::new();
 
-                    let language_tag = read_token(itr);
+    match input.peek() {
+        Some(c) if first(c) => {
+            name.push(c);
+            input.advance();
+        }
+        _ => return name,
+    }
 
-                    Some(Lexeme::LangLiteral(literal, language_tag))
-                }
-                Some((_, '^')) => {
-                    itr.nth(1);
+    while let Some(c) = input.peek() {
+        match c {
+            '.' if continues_after_dots(input, is_pn_chars) => {}
+            c if is_pn_chars(c) => {}
+            _ => break,
+        }
 
-                    let data_type = read_iri(itr);
+        name.push(c);
+        input.advance();
+    }
 
-                    match data_type {
-                        Lexeme::Iri(iri) => Some(Lexeme::DataTypeLiteral(literal, iri)),
-                        _ => Some(Lexeme::Unknown(literal)),
+    name
+}
+
+// PN_LOCAL, the part of a prefixed name after the `:`
+// `\`-escaped characters are unescaped, `%XX` is kept as it is
+fn read_local_name<R: BufRead>(input: &mut Cursor<R>) -> Result<String, &'static str> {
+    let mut local = String::new();
+
+    while let Some(c) = input.peek() {
+        match c {
+            '%' => {
+                input.advance();
+                local.push(c);
+
+                for _ in 0..2 {
+                    match input.peek() {
+                        Some(c) if c.is_ascii_hexdigit() => {
+                            local.push(c);
+                            input.advance();
+                        }
+                        _ => return Err("invalid percent encoding in local name, expected `%XX`"),
                     }
                 }
-                Some((_, '\"')) => {
-                    let (multiline_part, end) = read_multiline_part(itr);
-
-                    match end {
-                        true => {
-                            context.parse_multiline = false;
-                            context.parsed_multilines = Vec::new();
-                            Some(Lexeme::MultilineLiteral(multiline_part))
-                        }
-                        false => {
-                            context.parse_multiline = true;
-                            context
-                                .parsed_multilines
-                                .push(Lexeme::MultilineLiteral(multiline_part));
-                            None
-                        }
+            }
+            '\\' => {
+                input.advance();
+
+                match input.peek() {
+                    Some(c) if "_~.-!$&'()*+,;=/?#@%".contains(c) => {
+                        local.push(c);
+                        input.advance();
                     }
+                    _ => return Err("invalid escape sequence in local name"),
                 }
-                _ => Some(Lexeme::Literal(literal)),
             }
+            '.' if !local.is_empty() && continues_after_dots(input, is_local_char) => {
+                local.push(c);
+                input.advance();
+            }
+            c if is_pn_chars(c)
+                && (!local.is_empty() || is_pn_chars_u(c) || c.is_ascii_digit()) =>
+            {
+                local.push(c);
+                input.advance();
+            }
+            ':' => {
+                local.push(c);
+                input.advance();
+            }
+            _ => break,
         }
-        None => None,
     }
+
+    Ok(local)
 }
 
-//  read until end of triple quotes or end of line
-fn read_multiline_part(itr: &mut Peekable<Enumerate<Chars>>) -> (String, bool) {
-    let mut multi_line_part = String::new();
-    let mut consecutive_quotes = 0;
+// comparison and logical operators of sparql filters
+fn read_operator<R: BufRead>(input: &mut Cursor<R>) -> String {
+    let mut operator: String = input.advance().into_iter().collect();
+
+    if let (Some(first), Some(second)) = (operator.chars().next(), input.peek()) {
+        if matches!(
+            (first, second),
+            ('<' | '>' | '!', '=') | ('&', '&') | ('|', '|')
+        ) {
+            operator.push(second);
+            input.advance();
+        }
+    }
+
+    operator
+}
+
+// VARNAME of sparql, `?` and `$` are the same variable
+fn read_variable<R: BufRead>(input: &mut Cursor<R>) -> String {
+    let mut name = String::new();
+
+    while let Some(c) = input.peek() {
+        match c {
+            c if is_pn_chars_u(c) || c.is_ascii_digit() => name.push(c),
+            '\u{00B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}' if !name.is_empty() => {
+                name.push(c)
+            }
+            _ => break,
+        }
+
+        input.advance();
+    }
+
+    name
+}
+
+// whether the dots at the cursor are followed by a character matching `rest`
+fn continues_after_dots<R: BufRead>(input: &mut Cursor<R>, rest: fn(char) -> bool) -> bool {
+    let mut n = 0;
+
+    while input.peek_nth(n) == Some('.') {
+        n += 1;
+    }
+
+    input.peek_nth(n).is_some_and(rest)
+}
+
+fn is_local_char(c: char) -> bool {
+    is_pn_chars(c) || matches!(c, ':' | '%' | '\\')
+}
+
+pub fn is_pn_chars_base(c: char) -> bool {
+    matches!(c,
+        'A'..='Z'
+        | 'a'..='z'
+        | '\u{00C0}'..='\u{00D6}'
+        | '\u{00D8}'..='\u{00F6}'
+        | '\u{00F8}'..='\u{02FF}'
+        | '\u{0370}'..='\u{037D}'
+        | '\u{037F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}'
+        | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}')
+}
+
+pub fn is_pn_chars_u(c: char) -> bool {
+    is_pn_chars_base(c) || c == '_'
+}
+
+pub fn is_pn_chars(c: char) -> bool {
+    is_pn_chars_u(c)
+        || matches!(c,
+            '-' | '0'..='9' | '\u{00B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}')
+}
+
+// reads any of the four string forms, `"`, `'`, `"""` and `'''`
+fn read_literal<R: BufRead>(input: &mut Cursor<R>, context: &mut LexerContext) -> Option<Lexeme> {
+    let quote = input.peek()?;
+    let literal = read_literal_value(input, context, quote);
+
+    let lexeme = match literal {
+        // an empty literal followed by a third quote starts a long literal
+        Ok(literal) if literal.is_empty() && input.peek() == Some(quote) => {
+            input.advance();
+
+            let (multiline_part, end) = read_multiline_part(input, context, quote);
+
+            match end {
+                true => {
+                    context.parse_multiline = false;
+                    context.parsed_multilines = Vec::new();
+                    read_literal_suffix(input, context, multiline_part, Lexeme::MultilineLiteral)
+                }
+                false => {
+                    context.parse_multiline = true;
+                    context.multiline_quote = quote;
+                    context
+                        .parsed_multilines
+                        .push(Lexeme::MultilineLiteral(multiline_part));
+                    return None;
+                }
+            }
+        }
+        Ok(literal) => read_literal_suffix(input, context, literal, Lexeme::Literal),
+        Err(partial) => Lexeme::Unknown(format!("{}{}", quote, partial)),
+    };
+
+    Some(reject_invalid_escape(context, lexeme))
+}
+
+// a literal or iri with an escape that couldn't be decoded becomes unknown as a whole,
+// so no triple is made from what was left of it
+fn reject_invalid_escape(context: &mut LexerContext, lexeme: Lexeme) -> Lexeme {
+    match std::mem::take(&mut context.invalid_escape) {
+        true => Lexeme::Unknown(lexeme.to_string()),
+        false => lexeme,
+    }
+}
+
+// the language tag or datatype after a literal
+fn read_literal_suffix<R: BufRead>(
+    input: &mut Cursor<R>,
+    context: &mut LexerContext,
+    literal: String,
+    plain: fn(String) -> Lexeme,
+) -> Lexeme {
+    // a broken suffix keeps the quoted literal so errors show what was written
+    let invalid =
+        |literal: String, suffix: &str| Lexeme::Unknown(format!("{}{}", plain(literal), suffix));
+
+    match input.peek() {
+        Some('@') => {
+            input.advance();
+
+            match read_language_tag(input) {
+                Some(language_tag) => Lexeme::LangLiteral(literal, language_tag),
+                None => invalid(literal, "@"),
+            }
+        }
+        Some('^') => {
+            input.advance();
+
+            if input.advance() != Some('^') {
+                return invalid(literal, "^");
+            }
+
+            match input.peek() {
+                Some('<') => match read_iri(input, context) {
+                    Ok(iri) => Lexeme::DataTypeLiteral(literal, iri),
+                    Err(_) => invalid(literal, "^^"),
+                },
+                Some(c) if c == ':' || is_pn_chars_base(c) => {
+                    let prefix = read_name(input, is_pn_chars_base);
+
+                    if input.advance() != Some(':') {
+                        return invalid(literal, &format!("^^{}", prefix));
+                    }
 
-    // when called 2 quotes have already been read, check for 3rd to skip
-    match itr.peek() {
-        Some((_, '\"')) => {
-            itr.next();
+                    match read_local_name(input) {
+                        Ok(local) => Lexeme::PrefixedDataTypeLiteral(
+                            literal,
+                            format!("{}:{}", prefix, local),
+                        ),
+                        Err(_) => invalid(literal, &format!("^^{}:", prefix)),
+                    }
+                }
+                _ => invalid(literal, "^^"),
+            }
         }
-        _ => {}
+        _ => plain(literal),
     }
+}
 
-    while let Some((_, c)) = itr.peek() {
+//  read until end of triple quotes or end of input
+fn read_multiline_part<R: BufRead>(
+    input: &mut Cursor<R>,
+    context: &mut LexerContext,
+    quote: char,
+) -> (String, bool) {
+    let mut multi_line_part = String::new();
+    let mut consecutive_quotes = 0;
+
+    while let Some(c) = input.peek() {
         match c {
-            '\"' => {
-                multi_line_part.push(*c);
-                itr.next();
+            '\\' => {
+                match read_escape(input, context, true) {
+                    Some(c) => multi_line_part.push(c),
+                    None => context.invalid_escape = true,
+                }
+                consecutive_quotes = 0;
+            }
+            c if c == quote => {
+                multi_line_part.push(c);
+                input.advance();
                 consecutive_quotes += 1;
 
-                if consecutive_quotes == 3 {
+                // quotes right before the closing ones belong to the literal
+                if consecutive_quotes >= 3 && input.peek() != Some(quote) {
                     break;
                 }
             }
             _ => {
-                multi_line_part.push(*c);
-                itr.next();
+                multi_line_part.push(c);
+                input.advance();
                 consecutive_quotes = 0;
             }
         }
     }
 
-    (
-        multi_line_part.trim_end_matches("\"\"\"").to_string(),
-        consecutive_quotes == 3,
-    )
+    match consecutive_quotes >= 3 {
+        true => {
+            multi_line_part.truncate(multi_line_part.len() - 3);
+            (multi_line_part, true)
+        }
+        false => (multi_line_part, false),
+    }
 }
 
-fn read_literal_value(itr: &mut Peekable<Enumerate<Chars>>) -> Option<String> {
+// Err holds what was read of an unterminated literal
+fn read_literal_value<R: BufRead>(
+    input: &mut Cursor<R>,
+    context: &mut LexerContext,
+    quote: char,
+) -> Result<String, String> {
     let mut literal = String::new();
-    let mut found_end = false;
-    let mut is_escaped_quote = false;
-
-    let c = itr.next();
 
-    match c {
-        Some((_, '"')) => {}
-        _ => return None,
+    if input.advance() != Some(quote) {
+        return Err(literal);
     }
 
-    while let Some((_, c)) = itr.peek() {
+    while let Some(c) = input.peek() {
         match c {
-            '"' if !is_escaped_quote => {
-                itr.next();
-                found_end = true;
-                break;
-            }
-            '\\' => {
-                literal.push(*c);
-                itr.next();
-
-                // check if quote is escaped
-                let next_char = itr.peek();
-                is_escaped_quote = match next_char {
-                    Some((_, '"')) => true,
-                    _ => false,
-                }
+            c if c == quote => {
+                input.advance();
+                return Ok(literal);
             }
+            '\\' => match read_escape(input, context, true) {
+                Some(c) => literal.push(c),
+                None => context.invalid_escape = true,
+            },
+            '\n' | '\r' => break,
             _ => {
-                literal.push(*c);
-                itr.next();
-                is_escaped_quote = false;
+                literal.push(c);
+                input.advance();
             }
         }
     }
 
-    match found_end {
-        true => Some(literal),
-        false => None,
+    Err(literal)
+}
+
+// decodes the escape sequence at the `\`, `echar` allows `\n` and friends which iris don't
+fn read_escape<R: BufRead>(
+    input: &mut Cursor<R>,
+    context: &mut LexerContext,
+    echar: bool,
+) -> Option<char> {
+    let (line, start, offset) = (input.line, input.column, input.offset);
+
+    input.advance();
+
+    let escaped = match input.peek() {
+        Some(c @ ('t' | 'b' | 'n' | 'r' | 'f' | '"' | '\'' | '\\')) if echar => {
+            input.advance();
+
+            Ok(match c {
+                't' => '\t',
+                'b' => '\u{08}',
+                'n' => '\n',
+                'r' => '\r',
+                'f' => '\u{0C}',
+                c => c,
+            })
+        }
+        Some(c @ ('u' | 'U')) => {
+            input.advance();
+
+            let length = match c {
+                'u' => 4,
+                _ => 8,
+            };
+            let mut hex = String::new();
+
+            while hex.len() < length {
+                match input.peek() {
+                    Some(c) if c.is_ascii_hexdigit() => {
+                        hex.push(c);
+                        input.advance();
+                    }
+                    _ => break,
+                }
+            }
+
+            match u32::from_str_radix(&hex, 16) {
+                Ok(code_point) if hex.len() == length => {
+                    char::from_u32(code_point).ok_or_else(|| {
+                        format!("invalid code point `\\{}{}` in escape sequence", c, hex)
+                    })
+                }
+                _ => Err(format!(
+                    "invalid escape sequence `\\{}{}`, expected {} hex digits",
+                    c, hex, length
+                )),
+            }
+        }
+        Some(c) => Err(format!("invalid escape sequence `\\{}`", c)),
+        None => Err("invalid escape sequence `\\`".to_string()),
+    };
+
+    match escaped {
+        Ok(c) => Some(c),
+        Err(message) => {
+            context.diagnostics.push(Diagnostic::error(
+                &message,
+                Span {
+                    line,
+                    start,
+                    end: input.column,
+                    offset,
+                },
+            ));
+            None
+        }
     }
 }
 
 // read the next token until a whitespace is found
-fn read_token(itr: &mut Peekable<Enumerate<Chars>>) -> String {
+fn read_token<R: BufRead>(input: &mut Cursor<R>) -> String {
     let mut token = String::new();
 
-    while let Some((_, c)) = itr.peek() {
+    while let Some(c) = input.peek() {
         match c {
-            ' ' | ',' => {
-                break;
-            }
+            ',' | ';' | '[' | ']' | '(' | ')' | '{' | '}' => break,
+            c if c.is_whitespace() => break,
             _ => {
-                token.push(*c);
-                itr.next();
+                token.push(c);
+                input.advance();
             }
         }
     }
@@ -373,49 +1007,106 @@ fn read_token(itr: &mut Peekable<Enumerate<Chars>>) -> String {
     token
 }
 
-fn read_prefix(itr: &mut Peekable<Enumerate<Chars>>) -> (String, bool) {
-    let mut prefix = String::new();
-    let mut found_colon = false;
+// INTEGER, DECIMAL or DOUBLE, a `.` that isn't followed by digits ends the statement
+fn read_number<R: BufRead>(input: &mut Cursor<R>) -> String {
+    let mut number = String::new();
 
-    while let Some((_, c)) = itr.peek() {
-        match c {
-            ':' => {
-                found_colon = true;
-                prefix.push(*c);
-                itr.next();
-                break;
-            }
-            _ => {
-                prefix.push(*c);
-                itr.next();
-            }
+    if let Some(c @ ('+' | '-')) = input.peek() {
+        number.push(c);
+        input.advance();
+    }
+
+    read_digits(input, &mut number);
+
+    if input.peek() == Some('.')
+        && (input.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) || is_exponent(input, 1))
+    {
+        number.push('.');
+        input.advance();
+        read_digits(input, &mut number);
+    }
+
+    if is_exponent(input, 0) {
+        number.extend(input.advance());
+
+        if let Some(c @ ('+' | '-')) = input.peek() {
+            number.push(c);
+            input.advance();
         }
+
+        read_digits(input, &mut number);
+    }
+
+    // anything glued to the number makes it one unknown token
+    match input.peek() {
+        Some(c) if !c.is_whitespace() && !".,;[](){}#".contains(c) => number + &read_token(input),
+        _ => number,
     }
+}
 
-    (prefix, found_colon)
+fn read_digits<R: BufRead>(input: &mut Cursor<R>, number: &mut String) {
+    while let Some(c) = input.peek().filter(char::is_ascii_digit) {
+        number.push(c);
+        input.advance();
+    }
 }
 
-fn read_base(itr: &mut Peekable<Enumerate<Chars>>) -> Option<String> {
-    let iri = read_iri(itr);
+// whether an EXPONENT, `[eE] [+-]? [0-9]+`, starts `n` characters ahead
+fn is_exponent<R: BufRead>(input: &mut Cursor<R>, n: usize) -> bool {
+    let digit = match input.peek_nth(n + 1) {
+        Some('+' | '-') => n + 2,
+        _ => n + 1,
+    };
+
+    matches!(input.peek_nth(n), Some('e' | 'E'))
+        && input.peek_nth(digit).is_some_and(|c| c.is_ascii_digit())
+}
+
+// LANGTAG without the `@`, `[a-zA-Z]+ ('-' [a-zA-Z0-9]+)*`
+fn read_language_tag<R: BufRead>(input: &mut Cursor<R>) -> Option<String> {
+    let mut tag = String::new();
+
+    while let Some(c) = input.peek() {
+        match c {
+            c if c.is_ascii_alphabetic() => {}
+            c if c.is_ascii_digit() && tag.contains('-') => {}
+            '-' if !tag.is_empty()
+                && input.peek_nth(1).is_some_and(|c| c.is_ascii_alphanumeric()) => {}
+            _ => break,
+        }
 
-    match iri {
-        Lexeme::Iri(iri) => Some(iri),
-        _ => None,
+        tag.push(c);
+        input.advance();
     }
+
+    (!tag.is_empty()).then_some(tag)
 }
 
-fn skip_whitespace(itr: &mut Peekable<Enumerate<Chars>>) {
-    while let Some((_, c)) = itr.peek() {
+fn skip_whitespace<R: BufRead>(input: &mut Cursor<R>) {
+    while let Some(c) = input.peek() {
         if c.is_whitespace() {
-            itr.next();
+            input.advance();
         } else {
             break;
         }
     }
 }
 
-fn read_comment(itr: &mut Peekable<Enumerate<Chars>>) -> Lexeme {
-    let comment: String = itr.skip(1).map(|(_, c)| c).collect();
+// the comment runs until the end of the line
+fn read_comment<R: BufRead>(input: &mut Cursor<R>) -> Lexeme {
+    let mut comment = String::new();
+
+    input.advance();
+
+    while let Some(c) = input.peek() {
+        match c {
+            '\n' | '\r' => break,
+            _ => {
+                comment.push(c);
+                input.advance();
+            }
+        }
+    }
 
     Lexeme::Comment(comment)
 }
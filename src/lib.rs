@@ -1,2 +1,14 @@
+pub mod diagnostics;
+pub mod graph;
+pub mod iri;
 pub mod lexer;
+pub mod ntriples;
+pub mod number;
 pub mod parser;
+pub mod query;
+pub mod results;
+pub mod server;
+pub mod sparql;
+pub mod sparql_regex;
+pub mod term;
+pub mod turtle;
@@ -1,53 +1,275 @@
+use colored::*;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::{prelude::*, BufReader};
-use std::path::Path;
-use std::time::{Duration, Instant};
+use std::io::{self, prelude::*, BufReader};
+use std::net::TcpListener;
+use std::process::ExitCode;
+use std::time::Instant;
 
-mod lexer;
-use crate::lexer::*;
+use turtle_wa::diagnostics::*;
+use turtle_wa::graph::Graph;
+use turtle_wa::lexer::*;
+use turtle_wa::parser::{self, *};
+use turtle_wa::results::{self, ResultsFormat};
+use turtle_wa::server::{self, Server};
+use turtle_wa::sparql::Query;
+use turtle_wa::term::*;
+use turtle_wa::{ntriples, turtle};
 
-mod parser;
-use crate::parser::*;
+mod cli;
+use crate::cli::*;
 
 mod log;
 
-fn main() -> std::io::Result<()> {
-    let ttl_path = Path::new("./ttl/simple.ttl");
-    let file_result = File::open(ttl_path);
+const EXIT_INVALID_INPUT: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_IO: u8 = 3;
 
-    let f = match file_result {
-        Ok(file) => file,
-        Err(error) => {
-            println!("Couldn't find file {}", ttl_path.display());
-            return Err(error);
+// the result of parsing one input
+struct Document {
+    name: String,
+    triples: Vec<Triple>,
+    prefixes: HashMap<String, String>,
+    diagnostics: Vec<Diagnostic>,
+    lines: Vec<String>,
+}
+
+impl Document {
+    fn has_errors(&self) -> bool {
+        !self.diagnostics.is_empty()
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let (command, inputs) = match cli::parse_args(&args) {
+        Ok(Args::Run(command, inputs)) => (command, inputs),
+        Ok(Args::Help) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("{}: {}\n\n{}", "error".red(), message, USAGE);
+            return ExitCode::from(EXIT_USAGE);
         }
     };
-    let f = BufReader::new(f);
 
     let now = Instant::now();
-    let mut triples: Vec<Triple> = Vec::new();
+    let mut documents: Vec<Document> = Vec::new();
+
+    for input in &inputs {
+        match read_document(input) {
+            Ok(document) => documents.push(document),
+            Err(error) => {
+                eprintln!("{}: couldn't read {}: {}", "error".red(), input, error);
+                return ExitCode::from(EXIT_IO);
+            }
+        }
+    }
+
+    for document in &documents {
+        if !document.diagnostics.is_empty() {
+            eprintln!("{}", document.name.bold());
+        }
+
+        for diagnostic in &document.diagnostics {
+            report(diagnostic, &document.lines);
+        }
+    }
+
+    // the other commands have output of their own that timing would clutter
+    let timed = matches!(command, Command::Parse | Command::Stats);
+
+    let result = match command {
+        Command::Parse => print_triples(&documents),
+        Command::Validate => Ok(()),
+        Command::Stats => print_stats(&documents),
+        Command::Convert(format) => convert(&documents, format),
+        Command::Query(text, format) => {
+            // prefixes declared in the files can be used without declaring them in the query
+            let mut context = ParseContext::new();
+
+            context.prefixes = load_prefixes(&documents);
+
+            match Query::parse(&text, &mut context) {
+                Ok(query) => print_results(&documents, &query, format),
+                Err(error) => {
+                    eprintln!("{}: invalid query: {}", "error".red(), error);
+                    return ExitCode::from(EXIT_USAGE);
+                }
+            }
+        }
+        Command::Serve(port) => serve(&documents, port),
+    };
+
+    if let Err(error) = result {
+        // a closed pipe, e.g. `turtle_wa parse file.ttl | head`, is not an error
+        if error.kind() != io::ErrorKind::BrokenPipe {
+            eprintln!("{}: {}", "error".red(), error);
+            return ExitCode::from(EXIT_IO);
+        }
+    }
+
+    if timed {
+        let triples: usize = documents
+            .iter()
+            .map(|document| document.triples.len())
+            .sum();
+
+        eprintln!(
+            "Parse time: {}ms for {} triples",
+            now.elapsed().as_millis(),
+            triples
+        );
+    }
+
+    match documents.iter().any(Document::has_errors) {
+        true => ExitCode::from(EXIT_INVALID_INPUT),
+        false => ExitCode::SUCCESS,
+    }
+}
+
+fn read_document(input: &str) -> io::Result<Document> {
+    match input {
+        "-" => parse_document("<stdin>", io::stdin().lock()),
+        path => parse_document(path, BufReader::new(File::open(path)?)),
+    }
+}
+
+fn parse_document(name: &str, mut reader: impl BufRead) -> io::Result<Document> {
     let mut context = ParseContext::new();
-    let mut lexer_context = LexerContext::new();
+    let mut text = String::new();
+
+    reader.read_to_string(&mut text)?;
+
+    let mut lexer = Lexer::from(text.as_str());
+    let tokens = lexer.by_ref().collect::<io::Result<Vec<Token>>>()?;
+    let triples = parser::parse_collecting(&tokens, &mut context);
+
+    if let Err(error) = parser::finish(&mut context) {
+        context.diagnostics.push(Diagnostic::from(&error));
+    }
+
+    let mut diagnostics = lexer.take_diagnostics();
+    diagnostics.append(&mut context.diagnostics);
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.span.line, diagnostic.span.start));
 
-    let mut num_lines: u32 = 1;
-    for line in f.lines() {
-        let tokens = lexer::tokenize(&line.unwrap(), num_lines, &mut lexer_context);
-        let new_triples = parser::parse(&tokens, &mut context);
+    Ok(Document {
+        name: name.to_string(),
+        triples,
+        prefixes: context.prefixes,
+        diagnostics,
+        // kept to show the offending line of a diagnostic
+        lines: text.lines().map(str::to_string).collect(),
+    })
+}
 
-        triples.extend(new_triples);
+fn print_triples(documents: &[Document]) -> io::Result<()> {
+    let mut out = io::BufWriter::new(io::stdout().lock());
 
-        num_lines += 1;
+    for triple in documents.iter().flat_map(|document| &document.triples) {
+        writeln!(out, "{}", triple)?;
     }
 
-    for triple in &triples {
-        println!("{:?}", triple);
+    out.flush()
+}
+
+fn convert(documents: &[Document], format: Format) -> io::Result<()> {
+    let mut out = io::BufWriter::new(io::stdout().lock());
+
+    for document in documents {
+        match format {
+            Format::NTriples => ntriples::write_triples(&mut out, &document.triples)?,
+            Format::Turtle => {
+                turtle::write_triples(&mut out, &document.triples, &document.prefixes)?
+            }
+        }
     }
 
-    println!(
-        "Parse time: {}ms for {} triples",
-        now.elapsed().as_millis(),
-        triples.len()
+    out.flush()
+}
+
+// the triples of all documents in one graph
+fn load_graph(documents: &[Document]) -> Graph {
+    documents
+        .iter()
+        .flat_map(|document| document.triples.iter().cloned())
+        .collect()
+}
+
+// the prefixes of all documents, later documents win
+fn load_prefixes(documents: &[Document]) -> HashMap<String, String> {
+    documents
+        .iter()
+        .flat_map(|document| document.prefixes.clone())
+        .collect()
+}
+
+fn print_results(documents: &[Document], query: &Query, format: ResultsFormat) -> io::Result<()> {
+    let mut out = io::BufWriter::new(io::stdout().lock());
+
+    results::write_results(&mut out, &query.evaluate(&load_graph(documents)), format)?;
+    out.flush()
+}
+
+// only returns if the port can't be bound or the listener fails
+fn serve(documents: &[Document], port: u16) -> io::Result<()> {
+    let graph = load_graph(documents);
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+
+    eprintln!(
+        "Serving {} triples at http://{}/sparql",
+        graph.len(),
+        listener.local_addr()?
     );
 
+    server::serve(listener, &mut Server::new(graph, load_prefixes(documents)))
+}
+
+fn print_stats(documents: &[Document]) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+
+    writeln!(
+        out,
+        "{:<24} {:>10} {:>10} {:>10} {:>10} {:>8}",
+        "file", "triples", "subjects", "predicates", "objects", "errors"
+    )?;
+
+    for document in documents {
+        let triples = &document.triples;
+        let subjects: HashSet<&Subject> = triples.iter().map(|t| &t.subject).collect();
+        let predicates: HashSet<&NamedNode> = triples.iter().map(|t| &t.predicate).collect();
+        let objects: HashSet<&Term> = triples.iter().map(|t| &t.object).collect();
+        let errors = document.diagnostics.len();
+
+        writeln!(
+            out,
+            "{:<24} {:>10} {:>10} {:>10} {:>10} {:>8}",
+            document.name,
+            triples.len(),
+            subjects.len(),
+            predicates.len(),
+            objects.len(),
+            errors
+        )?;
+    }
+
     Ok(())
 }
+
+fn report(diagnostic: &Diagnostic, lines: &[String]) {
+    let span = diagnostic.span;
+    let line = match span.line.checked_sub(1) {
+        Some(index) => lines.get(index as usize).cloned().unwrap_or_default(),
+        None => String::new(),
+    };
+    let token: String = line
+        .chars()
+        .skip(span.start)
+        .take(span.end.saturating_sub(span.start).max(1))
+        .collect();
+    let message = diagnostic.message.clone();
+
+    log::log_error(message, line, token, span.line, span.start);
+}
@@ -0,0 +1,281 @@
+// n-triples and n-quads, the line based subsets of turtle and trig
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+
+use crate::iri;
+use crate::lexer::{self, Lexeme, LexerContext, Span, Token};
+use crate::parser::{syntax_error, ParseContext, ParseError};
+use crate::term::*;
+
+// writes one triple per line in canonical n-triples
+pub fn write_triples<W: Write>(out: &mut W, triples: &[Triple]) -> io::Result<()> {
+    for triple in triples {
+        writeln!(out, "{}", triple)?;
+    }
+
+    Ok(())
+}
+
+pub fn literal_to_string(literal: &str) -> String {
+    let mut escaped = String::with_capacity(literal.len() + 2);
+
+    escaped.push('"');
+
+    for c in literal.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{08}' => escaped.push_str("\\b"),
+            '\u{0C}' => escaped.push_str("\\f"),
+            // rdf 1.2 canonical n-triples writes the other control characters as uppercase \u
+            c if c.is_control() && c <= '\u{7F}' => {
+                let _ = write!(escaped, "\\u{:04X}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+pub fn iri_to_string(iri: &str) -> String {
+    let mut escaped = String::with_capacity(iri.len() + 2);
+
+    escaped.push('<');
+
+    // characters that aren't allowed in an IRIREF
+    for c in iri.chars() {
+        match c {
+            '\u{00}'..='\u{20}' | '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' => {
+                let _ = write!(escaped, "\\u{:04X}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('>');
+    escaped
+}
+
+// parses n-triples lazily, one statement per line without any turtle shortcuts
+pub struct NTriplesParser<R> {
+    lines: LineParser<R>,
+}
+
+impl<R: BufRead> NTriplesParser<R> {
+    pub fn new(reader: R) -> NTriplesParser<R> {
+        NTriplesParser {
+            lines: LineParser::new(reader, false),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for NTriplesParser<&'a [u8]> {
+    fn from(text: &'a str) -> Self {
+        NTriplesParser::new(text.as_bytes())
+    }
+}
+
+impl<R: BufRead> Iterator for NTriplesParser<R> {
+    type Item = Result<Triple, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next().map(|result| result.map(Triple::from))
+    }
+}
+
+// parses n-quads lazily, like n-triples with an optional graph name before the `.`
+pub struct NQuadsParser<R> {
+    lines: LineParser<R>,
+}
+
+impl<R: BufRead> NQuadsParser<R> {
+    pub fn new(reader: R) -> NQuadsParser<R> {
+        NQuadsParser {
+            lines: LineParser::new(reader, true),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for NQuadsParser<&'a [u8]> {
+    fn from(text: &'a str) -> Self {
+        NQuadsParser::new(text.as_bytes())
+    }
+}
+
+impl<R: BufRead> Iterator for NQuadsParser<R> {
+    type Item = Result<Quad, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next()
+    }
+}
+
+struct LineParser<R> {
+    lines: io::Lines<R>,
+    line_num: u32,
+    context: ParseContext, // only used for blank node labels
+    quads: bool,
+    finished: bool,
+}
+
+impl<R: BufRead> LineParser<R> {
+    fn new(reader: R, quads: bool) -> LineParser<R> {
+        LineParser {
+            lines: reader.lines(),
+            line_num: 0,
+            context: ParseContext::new(),
+            quads,
+            finished: false,
+        }
+    }
+
+    fn next(&mut self) -> Option<Result<Quad, ParseError>> {
+        while !self.finished {
+            self.line_num += 1;
+
+            match self.lines.next() {
+                Some(Ok(line)) => {
+                    match parse_line(&line, self.line_num, &mut self.context, self.quads) {
+                        Ok(None) => {}
+                        Ok(Some(quad)) => return Some(Ok(quad)),
+                        Err(error) => return Some(Err(error)),
+                    }
+                }
+                Some(Err(error)) => {
+                    self.finished = true;
+
+                    return Some(Err(ParseError::Io {
+                        message: error.to_string(),
+                        span: Span {
+                            line: self.line_num,
+                            ..Span::default()
+                        },
+                    }));
+                }
+                None => self.finished = true,
+            }
+        }
+
+        None
+    }
+}
+
+// a statement is `subject predicate object graph? .`, None for empty and comment lines
+fn parse_line(
+    line: &str,
+    line_num: u32,
+    context: &mut ParseContext,
+    quads: bool,
+) -> Result<Option<Quad>, ParseError> {
+    let mut lexer_context = LexerContext::new();
+    let tokens = lexer::tokenize(line, line_num, &mut lexer_context);
+
+    // literals can't span lines
+    lexer::finish(&mut lexer_context);
+
+    if let Some(diagnostic) = lexer_context.diagnostics.into_iter().next() {
+        return Err(syntax_error(diagnostic));
+    }
+
+    let tokens: Vec<&Token> = tokens
+        .iter()
+        .filter(|token| !matches!(token.lexeme, Lexeme::Comment(_)))
+        .collect();
+
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+
+    let token = |i: usize| {
+        tokens
+            .get(i)
+            .copied()
+            .ok_or(ParseError::UnexpectedEndOfInput {
+                span: tokens[tokens.len() - 1].span,
+            })
+    };
+
+    let subject = match &token(0)?.lexeme {
+        Lexeme::Iri(iri) => Subject::from(named_node(iri, token(0)?)?),
+        Lexeme::BlankNodeLabel(label) => Subject::from(context.blank_node(label)),
+        _ => return Err(unexpected_token(token(0)?, line)),
+    };
+    let predicate = match &token(1)?.lexeme {
+        Lexeme::Iri(iri) => named_node(iri, token(1)?)?,
+        _ => return Err(unexpected_token(token(1)?, line)),
+    };
+    let object = match &token(2)?.lexeme {
+        Lexeme::Iri(iri) => Term::from(named_node(iri, token(2)?)?),
+        Lexeme::BlankNodeLabel(label) => Term::from(context.blank_node(label)),
+        _ => Term::from(literal(token(2)?, line)?),
+    };
+    let graph_name = match &token(3)?.lexeme {
+        Lexeme::Iri(iri) if quads => Some(GraphName::from(named_node(iri, token(3)?)?)),
+        Lexeme::BlankNodeLabel(label) if quads => Some(GraphName::from(context.blank_node(label))),
+        _ => None,
+    };
+    let end = 3 + graph_name.is_some() as usize;
+
+    if token(end)?.lexeme != Lexeme::EndToken {
+        return Err(unexpected_token(token(end)?, line));
+    }
+
+    if let Some(token) = tokens.get(end + 1) {
+        return Err(unexpected_token(token, line));
+    }
+
+    Ok(Some(Quad {
+        subject,
+        predicate,
+        object,
+        graph_name,
+    }))
+}
+
+// only absolute iris, there is no base to resolve against
+fn named_node(iri: &str, token: &Token) -> Result<NamedNode, ParseError> {
+    match iri::is_absolute(iri) {
+        true => Ok(NamedNode::new(iri)),
+        false => Err(ParseError::RelativeIriWithoutBase {
+            iri: iri.to_string(),
+            span: token.span,
+        }),
+    }
+}
+
+// only `"..."` strings, optionally with a language tag or an iri datatype, no `"""..."""`
+fn literal(token: &Token, line: &str) -> Result<Literal, ParseError> {
+    let source = &line[token.span.offset..];
+
+    if !source.starts_with('"') || source.starts_with("\"\"\"") {
+        return Err(unexpected_token(token, line));
+    }
+
+    match &token.lexeme {
+        Lexeme::Literal(value) => Ok(Literal::new_simple(value)),
+        Lexeme::LangLiteral(value, language) => Ok(Literal::new_language_tagged(value, language)),
+        Lexeme::DataTypeLiteral(value, datatype) => {
+            Ok(Literal::new_typed(value, named_node(datatype, token)?.0))
+        }
+        _ => Err(unexpected_token(token, line)),
+    }
+}
+
+// the token as it was written, prefixed names, `a` and numbers are turtle only
+fn unexpected_token(token: &Token, line: &str) -> ParseError {
+    let text: String = line
+        .chars()
+        .skip(token.span.start)
+        .take(token.span.end - token.span.start)
+        .collect();
+
+    ParseError::UnexpectedToken {
+        token: text,
+        span: token.span,
+    }
+}
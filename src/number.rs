@@ -0,0 +1,218 @@
+// xsd:integer, xsd:decimal and xsd:double literals, kept as they were written
+use std::cmp::Ordering;
+use std::fmt;
+
+// an exact integer of any size
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct BigInt {
+    negative: bool,
+    digits: String, // without leading zeros, zero is never negative
+}
+
+impl BigInt {
+    fn new(negative: bool, digits: &str) -> BigInt {
+        match digits.trim_start_matches('0') {
+            "" => BigInt {
+                negative: false,
+                digits: "0".to_string(),
+            },
+            digits => BigInt {
+                negative,
+                digits: digits.to_string(),
+            },
+        }
+    }
+
+    // `[+-]? [0-9]+`
+    pub fn parse(lexical: &str) -> Option<BigInt> {
+        let (negative, digits) = split_sign(lexical);
+
+        is_digits(digits).then(|| BigInt::new(negative, digits))
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        self.to_string().parse().ok()
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(integer: i64) -> Self {
+        BigInt::new(integer < 0, &integer.unsigned_abs().to_string())
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.negative {
+            true => write!(f, "-{}", self.digits),
+            false => write!(f, "{}", self.digits),
+        }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // without leading zeros the longer magnitude is the larger one
+        let magnitude = || {
+            self.digits
+                .len()
+                .cmp(&other.digits.len())
+                .then_with(|| self.digits.cmp(&other.digits))
+        };
+
+        match (self.negative, other.negative) {
+            (false, false) => magnitude(),
+            (true, true) => magnitude().reverse(),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// an exact decimal, `unscaled * 10^-scale`
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct BigDecimal {
+    unscaled: BigInt,
+    scale: usize, // digits after the `.`, without trailing zeros
+}
+
+impl BigDecimal {
+    // `[+-]? [0-9]* ('.' [0-9]*)?` with at least one digit
+    pub fn parse(lexical: &str) -> Option<BigDecimal> {
+        let (negative, number) = split_sign(lexical);
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        if integer.is_empty() && fraction.is_empty() {
+            return None;
+        }
+
+        if !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let fraction = fraction.trim_end_matches('0');
+
+        Some(BigDecimal {
+            unscaled: BigInt::new(negative, &format!("{}{}", integer, fraction)),
+            scale: fraction.len(),
+        })
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.unscaled.negative
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    // the unscaled value with zeros appended until it has the given scale
+    fn rescale(&self, scale: usize) -> BigInt {
+        let zeros = "0".repeat(scale - self.scale);
+
+        BigInt::new(
+            self.unscaled.negative,
+            &(self.unscaled.digits.clone() + &zeros),
+        )
+    }
+}
+
+impl From<BigInt> for BigDecimal {
+    fn from(integer: BigInt) -> Self {
+        BigDecimal {
+            unscaled: integer,
+            scale: 0,
+        }
+    }
+}
+
+// the canonical form of xsd:decimal, always with a digit on both sides of the `.`
+impl fmt::Display for BigDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = &self.unscaled.digits;
+        let digits = match digits.len() > self.scale {
+            true => digits.to_string(),
+            false => format!("{}{}", "0".repeat(self.scale + 1 - digits.len()), digits),
+        };
+        let (integer, fraction) = digits.split_at(digits.len() - self.scale);
+        let sign = match self.unscaled.negative {
+            true => "-",
+            false => "",
+        };
+
+        match fraction {
+            "" => write!(f, "{}{}.0", sign, integer),
+            fraction => write!(f, "{}{}.{}", sign, integer, fraction),
+        }
+    }
+}
+
+impl Ord for BigDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+
+        self.rescale(scale).cmp(&other.rescale(scale))
+    }
+}
+
+impl PartialOrd for BigDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// INTEGER, `[+-]? [0-9]+`
+pub fn is_integer(lexical: &str) -> bool {
+    is_digits(split_sign(lexical).1)
+}
+
+// DECIMAL, `[+-]? [0-9]* '.' [0-9]+`
+pub fn is_decimal(lexical: &str) -> bool {
+    match split_sign(lexical).1.split_once('.') {
+        Some((integer, fraction)) => {
+            integer.chars().all(|c| c.is_ascii_digit()) && is_digits(fraction)
+        }
+        None => false,
+    }
+}
+
+// DOUBLE, `[+-]? ([0-9]+ '.' [0-9]* | '.' [0-9]+ | [0-9]+) [eE] [+-]? [0-9]+`,
+// numbers out of range for a f64 are valid and kept as they were written
+pub fn is_double(lexical: &str) -> bool {
+    let Some((mantissa, exponent)) = split_sign(lexical).1.split_once(['e', 'E']) else {
+        return false;
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let is_mantissa = !(integer.is_empty() && fraction.is_empty())
+        && integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit());
+
+    is_mantissa && is_digits(split_sign(exponent).1)
+}
+
+fn split_sign(lexical: &str) -> (bool, &str) {
+    match lexical.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, lexical.strip_prefix('+').unwrap_or(lexical)),
+    }
+}
+
+fn is_digits(digits: &str) -> bool {
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
@@ -1,69 +1,182 @@
-use http::Uri;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, BufRead};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-// todo parser
+use crate::diagnostics::Diagnostic;
+use crate::iri;
 use crate::lexer::*;
+use crate::number;
+use crate::term::*;
 
-#[derive(PartialEq, Debug, Clone)]
-pub enum Object {
-    Iri(String),
-    Literal(String),
-    LangLiteral(String, String),
-    DataTypeLiteral(String, String),
-    Boolean(bool),
-    Integer(i32),
-    Decimal(f32),
-    Double(f64),
-    MultilineLiteral(String),
-}
-
-impl From<Iri> for Object {
-    fn from(item: Iri) -> Self {
-        Object::Iri(item.0)
-    }
-}
-
-impl TryFrom<Lexeme> for Object {
-    type Error = ();
-
-    fn try_from(lexeme: Lexeme) -> Result<Self, Self::Error> {
-        match lexeme {
-            Lexeme::Iri(iri) => Ok(Object::Iri(iri)),
-            Lexeme::Literal(literal) => Ok(Object::Literal(literal)),
-            Lexeme::LangLiteral(literal, lang) => Ok(Object::LangLiteral(literal, lang)),
-            Lexeme::DataTypeLiteral(literal, datatype) => {
-                Ok(Object::DataTypeLiteral(literal, datatype))
+// receives everything parse finds, in document order
+pub trait TripleSink {
+    fn triple(&mut self, triple: Triple);
+
+    // triples of a trig document, sinks that only take triples drop the graph name
+    fn quad(&mut self, quad: Quad) {
+        self.triple(Triple::from(quad));
+    }
+
+    // the name includes the colon, like the keys of ParseContext.prefixes
+    fn prefix(&mut self, _name: &str, _iri: &str) {}
+
+    fn base(&mut self, _iri: &str) {}
+}
+
+impl TripleSink for Vec<Triple> {
+    fn triple(&mut self, triple: Triple) {
+        self.push(triple);
+    }
+}
+
+impl TripleSink for Vec<Quad> {
+    fn triple(&mut self, triple: Triple) {
+        self.push(Quad::from(triple));
+    }
+
+    fn quad(&mut self, quad: Quad) {
+        self.push(quad);
+    }
+}
+
+// every ParseContext gets its own id so blank node labels of different documents never clash
+static DOCUMENT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// subject, predicate and pending object of the statement that is currently parsed
+#[derive(Default)]
+struct Statement {
+    subject: Option<Subject>,
+    predicate: Option<NamedNode>,
+    object: Option<Term>,
+    complete: bool, // a predicate and an object were read, so the statement may end after a `;`
+}
+
+impl Statement {
+    fn with_subject(subject: Subject) -> Statement {
+        Statement {
+            subject: Some(subject),
+            predicate: None,
+            object: None,
+            complete: false,
+        }
+    }
+
+    // `.`, `;` and `}` are only allowed after an object or after the `;` that follows one
+    fn can_end(&self) -> bool {
+        self.object.is_some() || self.predicate.is_none() && self.complete
+    }
+
+    // the pending object as triple, keeps subject and predicate for object and predicate lists
+    fn flush(&mut self) -> Option<Triple> {
+        match (&self.subject, &self.predicate, self.object.take()) {
+            (Some(subject), Some(predicate), Some(object)) => {
+                self.complete = true;
+
+                Some(Triple {
+                    subject: subject.clone(),
+                    predicate: predicate.clone(),
+                    object,
+                })
             }
-            _ => Err(()),
+            _ => None,
         }
     }
 }
 
+// first and last list cell of a `( ... )` collection, None until the first item is parsed
+#[derive(Default)]
+struct Collection {
+    head: Option<BlankNode>,
+    last: Option<BlankNode>,
+}
+
+enum Frame {
+    Statement(Statement),   // top level statement or `[ ... ]`
+    Collection(Collection), // `( ... )`
+}
+
 #[derive(PartialEq, Debug, Clone)]
-pub struct Iri(pub String);
+pub enum ParseError {
+    UndefinedPrefix { prefix: String, span: Span },
+    RelativeIriWithoutBase { iri: String, span: Span },
+    UnexpectedToken { token: String, span: Span },
+    UnterminatedLiteral { literal: String, span: Span },
+    InvalidSyntax { message: String, span: Span }, // input the lexer couldn't turn into a token
+    UnexpectedEndOfInput { span: Span },
+    UnclosedGraph { span: Span },
+    Io { message: String, span: Span },
+}
+
+impl ParseError {
+    // the offending token
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UndefinedPrefix { span, .. }
+            | ParseError::RelativeIriWithoutBase { span, .. }
+            | ParseError::UnexpectedToken { span, .. }
+            | ParseError::UnterminatedLiteral { span, .. }
+            | ParseError::InvalidSyntax { span, .. }
+            | ParseError::UnexpectedEndOfInput { span }
+            | ParseError::UnclosedGraph { span }
+            | ParseError::Io { span, .. } => *span,
+        }
+    }
 
-impl TryFrom<Lexeme> for Iri {
-    type Error = ();
+    pub fn line(&self) -> u32 {
+        self.span().line
+    }
 
-    fn try_from(lexeme: Lexeme) -> Result<Self, Self::Error> {
-        match lexeme {
-            Lexeme::Iri(iri) => Ok(Iri(iri)),
-            _ => Err(()),
+    pub fn column(&self) -> usize {
+        self.span().start
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::UndefinedPrefix { prefix, .. } => format!("undefined prefix `{}`", prefix),
+            ParseError::RelativeIriWithoutBase { iri, .. } => {
+                format!("relative iri `<{}>` without @base", iri)
+            }
+            ParseError::UnexpectedToken { token, .. } => format!("unexpected token `{}`", token),
+            ParseError::UnterminatedLiteral { literal, .. } => {
+                format!("unterminated literal `{}`", literal)
+            }
+            ParseError::InvalidSyntax { message, .. } => message.to_string(),
+            ParseError::UnexpectedEndOfInput { .. } => {
+                "unexpected end of input, statement is missing `.`".to_string()
+            }
+            ParseError::UnclosedGraph { .. } => {
+                "unexpected end of input, graph is missing `}`".to_string()
+            }
+            ParseError::Io { message, .. } => format!("couldn't read input: {}", message),
         }
     }
 }
 
-#[derive(PartialEq, Debug)]
-pub struct Triple {
-    pub subject: Iri,
-    pub predicate: Iri,
-    pub object: Object,
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{} - {}", self.line(), self.column(), self.message())
+    }
 }
 
+impl std::error::Error for ParseError {}
+
 pub struct ParseContext {
     pub base: Option<String>,
     pub prefixes: HashMap<String, String>,
-    pub subject: Option<Iri>, // save subject when encountering a predicate list
+    // the top level statement followed by one frame per open `[` or `(`, kept across lines
+    frames: Vec<Frame>,
+    document_id: usize,
+    blank_nodes: HashMap<String, BlankNode>,
+    blank_node_count: usize,
+    pub diagnostics: Vec<Diagnostic>, // errors recorded by parse_collecting
+    recovering: bool,                 // skipping tokens until the end of a broken statement
+    last_span: Span,                  // the last parsed token
+    trig: bool,                       // `{ ... }` blocks and GRAPH are allowed
+    in_graph: bool,                   // inside of a `{ ... }` block
+    graph: Option<GraphName>,         // the name of the current block, None for the default graph
+    graph_keyword: bool,              // GRAPH was read, its label and `{` have to follow
+    label: bool,                      // the last token was a subject that can name a graph
 }
 
 impl ParseContext {
@@ -71,265 +184,777 @@ impl ParseContext {
         ParseContext {
             base: None,
             prefixes: HashMap::new(),
-            subject: None,
+            frames: vec![Frame::Statement(Statement::default())],
+            document_id: DOCUMENT_COUNT.fetch_add(1, Ordering::Relaxed),
+            blank_nodes: HashMap::new(),
+            blank_node_count: 0,
+            diagnostics: Vec::new(),
+            recovering: false,
+            last_span: Span::default(),
+            trig: false,
+            in_graph: false,
+            graph: None,
+            graph_keyword: false,
+            label: false,
+        }
+    }
+
+    // a context for trig documents, its triples go to the sink as quads
+    pub fn new_trig() -> ParseContext {
+        ParseContext {
+            trig: true,
+            ..ParseContext::new()
+        }
+    }
+
+    // the trig graph that the current statement belongs to
+    pub fn graph(&self) -> Option<&GraphName> {
+        self.graph.as_ref()
+    }
+
+    // returns the blank node for a label, the same label always maps to the same node within a document
+    pub fn blank_node(&mut self, label: &str) -> BlankNode {
+        if let Some(blank_node) = self.blank_nodes.get(label) {
+            return blank_node.clone();
+        }
+
+        let blank_node = self.fresh_blank_node();
+
+        self.blank_nodes
+            .insert(label.to_string(), blank_node.clone());
+
+        blank_node
+    }
+
+    // generates a new blank node that is unique across documents
+    pub fn fresh_blank_node(&mut self) -> BlankNode {
+        let blank_node = BlankNode(format!("d{}b{}", self.document_id, self.blank_node_count));
+
+        self.blank_node_count += 1;
+
+        blank_node
+    }
+
+    // the innermost statement, None while inside of a collection
+    fn statement(&mut self) -> Option<&mut Statement> {
+        match self.frames.last_mut() {
+            Some(Frame::Statement(statement)) => Some(statement),
+            _ => None,
+        }
+    }
+
+    // an empty statement or one that can end, see Statement::can_end
+    fn statement_can_end(&mut self) -> bool {
+        self.statement()
+            .is_some_and(|statement| statement.subject.is_none() || statement.can_end())
+    }
+
+    // place a subject, object or collection item in the innermost frame
+    fn add_term(
+        &mut self,
+        term: Term,
+        token: &Token,
+        sink: &mut impl TripleSink,
+    ) -> Result<(), ParseError> {
+        if let Some(Frame::Collection(_)) = self.frames.last() {
+            let cell = self.fresh_blank_node();
+
+            if let Some(Frame::Collection(collection)) = self.frames.last_mut() {
+                match collection.last.replace(cell.clone()) {
+                    Some(last) => {
+                        let rest = Triple {
+                            subject: Subject::from(last),
+                            predicate: NamedNode::new(RDF_REST),
+                            object: Term::from(cell.clone()),
+                        };
+
+                        self.emit(rest, sink);
+                    }
+                    None => collection.head = Some(cell.clone()),
+                }
+            }
+
+            self.emit(
+                Triple {
+                    subject: Subject::from(cell),
+                    predicate: NamedNode::new(RDF_FIRST),
+                    object: term,
+                },
+                sink,
+            );
+
+            return Ok(());
+        }
+
+        let error = unexpected_token(token);
+
+        match self.statement() {
+            Some(statement @ Statement { subject: None, .. }) => {
+                statement.subject = Some(Subject::try_from(term).map_err(|_| error)?);
+            }
+            Some(
+                statement @ Statement {
+                    predicate: Some(_),
+                    object: None,
+                    ..
+                },
+            ) => {
+                statement.object = Some(term);
+            }
+            _ => return Err(error),
+        }
+
+        Ok(())
+    }
+
+    // triples of a trig document go to the sink as quads of the current graph
+    fn emit(&self, triple: Triple, sink: &mut impl TripleSink) {
+        match self.trig {
+            true => sink.quad(Quad::new(triple, self.graph.clone())),
+            false => sink.triple(triple),
+        }
+    }
+
+    // emits the pending triple of the innermost statement
+    fn flush(&mut self, sink: &mut impl TripleSink) {
+        if let Some(triple) = self.statement().and_then(Statement::flush) {
+            self.emit(triple, sink);
         }
     }
 }
 
-pub fn parse(lexemes: &Vec<Lexeme>, context: &mut ParseContext) -> Vec<Triple> {
+impl Default for ParseContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn parse(tokens: &[Token], context: &mut ParseContext) -> Result<Vec<Triple>, ParseError> {
     let mut triples: Vec<Triple> = Vec::new();
-    let mut current_triple: (Option<Iri>, Option<Iri>, Option<Object>) =
-        (context.subject.to_owned(), None, None);
-    let mut itr = lexemes.iter().peekable();
-    let mut is_incomplete_triple = true;
-
-    while let Some(lexeme) = itr.next() {
-        match lexeme {
-            Lexeme::Iri(iri) => match current_triple {
-                (None, None, None) => {
-                    let iri = match parse_iri(lexeme, context) {
-                        Some(iri) => current_triple.0 = Some(iri),
-                        None => continue,
-                    };
-                }
-                (Some(_), None, None) => {
-                    let iri = match parse_iri(lexeme, context) {
-                        Some(iri) => current_triple.1 = Some(iri),
-                        None => continue,
-                    };
+
+    parse_into(tokens, context, &mut triples)?;
+
+    Ok(triples)
+}
+
+// like parse, but hands triples, prefixes and base to the sink as soon as they are found
+pub fn parse_into(
+    tokens: &[Token],
+    context: &mut ParseContext,
+    sink: &mut impl TripleSink,
+) -> Result<(), ParseError> {
+    for token in tokens {
+        parse_token(token, context, sink)?;
+    }
+
+    Ok(())
+}
+
+// parses a whole document into the sink without collecting its tokens or triples
+pub fn parse_reader_into(
+    reader: impl BufRead,
+    context: &mut ParseContext,
+    sink: &mut impl TripleSink,
+) -> Result<(), ParseError> {
+    let mut lexer = Lexer::new(reader);
+
+    loop {
+        let token = lexer.next();
+
+        if let Some(diagnostic) = lexer.take_diagnostics().into_iter().next() {
+            return Err(syntax_error(diagnostic));
+        }
+
+        match token {
+            Some(Ok(token)) => parse_token(&token, context, sink)?,
+            Some(Err(error)) => return Err(io_error(&error, context)),
+            None => return finish(context),
+        }
+    }
+}
+
+// like parse, but records errors in context.diagnostics and skips the rest of the broken statement
+pub fn parse_collecting(tokens: &[Token], context: &mut ParseContext) -> Vec<Triple> {
+    let mut triples: Vec<Triple> = Vec::new();
+
+    for token in tokens {
+        if let Err(error) = parse_recovering(token, context, &mut triples) {
+            context.diagnostics.push(Diagnostic::from(&error));
+        }
+    }
+
+    triples
+}
+
+// reports a statement that is still open at the end of the input
+pub fn finish(context: &mut ParseContext) -> Result<(), ParseError> {
+    let open = match context.frames.as_slice() {
+        [Frame::Statement(statement)] => statement.subject.is_some(),
+        _ => true,
+    };
+
+    let in_graph = std::mem::take(&mut context.in_graph);
+
+    context.frames = vec![Frame::Statement(Statement::default())];
+    context.graph = None;
+    context.graph_keyword = false;
+
+    match (open && !context.recovering, in_graph) {
+        (true, _) => Err(ParseError::UnexpectedEndOfInput {
+            span: context.last_span,
+        }),
+        (false, true) => Err(ParseError::UnclosedGraph {
+            span: context.last_span,
+        }),
+        (false, false) => Ok(()),
+    }
+}
+
+// parses a token, after an error the rest of the broken statement is skipped
+fn parse_recovering(
+    token: &Token,
+    context: &mut ParseContext,
+    sink: &mut impl TripleSink,
+) -> Result<(), ParseError> {
+    if context.recovering {
+        context.recovering = !ends_statement(&token.lexeme);
+
+        // a `}` also closes the graph of the broken statement
+        if token.lexeme != Lexeme::GraphEndToken || !context.in_graph {
+            return Ok(());
+        }
+    }
+
+    let result = parse_token(token, context, sink);
+
+    if result.is_err() {
+        context.frames = vec![Frame::Statement(Statement::default())];
+        context.graph_keyword = false;
+        context.recovering = !ends_statement(&token.lexeme);
+    }
+
+    result
+}
+
+fn ends_statement(lexeme: &Lexeme) -> bool {
+    matches!(lexeme, Lexeme::EndToken | Lexeme::GraphEndToken)
+}
+
+// parses a document lazily, yielding triples as soon as their statement is complete
+pub struct TurtleParser<R> {
+    stream: Stream<R, Triple>,
+}
+
+impl<R: BufRead> TurtleParser<R> {
+    pub fn new(reader: R) -> TurtleParser<R> {
+        TurtleParser {
+            stream: Stream::new(reader, ParseContext::new()),
+        }
+    }
+
+    // base and prefixes that were declared so far
+    pub fn context(&self) -> &ParseContext {
+        &self.stream.context
+    }
+}
+
+impl<'a> From<&'a str> for TurtleParser<&'a [u8]> {
+    fn from(text: &'a str) -> Self {
+        TurtleParser::new(text.as_bytes())
+    }
+}
+
+impl<R: BufRead> Iterator for TurtleParser<R> {
+    type Item = Result<Triple, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stream.next()
+    }
+}
+
+// parses a trig document lazily, triples outside of a `{ ... }` block are in the default graph
+pub struct TrigParser<R> {
+    stream: Stream<R, Quad>,
+}
+
+impl<R: BufRead> TrigParser<R> {
+    pub fn new(reader: R) -> TrigParser<R> {
+        TrigParser {
+            stream: Stream::new(reader, ParseContext::new_trig()),
+        }
+    }
+
+    // base and prefixes that were declared so far
+    pub fn context(&self) -> &ParseContext {
+        &self.stream.context
+    }
+}
+
+impl<'a> From<&'a str> for TrigParser<&'a [u8]> {
+    fn from(text: &'a str) -> Self {
+        TrigParser::new(text.as_bytes())
+    }
+}
+
+impl<R: BufRead> Iterator for TrigParser<R> {
+    type Item = Result<Quad, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stream.next()
+    }
+}
+
+// the lexer and parser state shared by TurtleParser and TrigParser
+struct Stream<R, T> {
+    lexer: Lexer<R>,
+    context: ParseContext,
+    pending: VecDeque<Result<T, ParseError>>,
+    finished: bool,
+}
+
+impl<R: BufRead, T> Stream<R, T>
+where
+    Vec<T>: TripleSink,
+{
+    fn new(reader: R, context: ParseContext) -> Stream<R, T> {
+        Stream {
+            lexer: Lexer::new(reader),
+            context,
+            pending: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    fn next(&mut self) -> Option<Result<T, ParseError>> {
+        while self.pending.is_empty() && !self.finished {
+            let token = self.lexer.next();
+
+            for diagnostic in self.lexer.take_diagnostics() {
+                self.pending.push_back(Err(syntax_error(diagnostic)));
+            }
+
+            match token {
+                Some(Ok(token)) => {
+                    let mut items: Vec<T> = Vec::new();
+                    let result = parse_recovering(&token, &mut self.context, &mut items);
+
+                    self.pending.extend(items.into_iter().map(Ok));
+
+                    if let Err(error) = result {
+                        self.pending.push_back(Err(error));
+                    }
                 }
-                (Some(_), Some(_), None) => {
-                    let iri = match parse_iri(lexeme, context) {
-                        Some(iri) => current_triple.2 = Some(Object::from(iri)),
-                        None => continue,
-                    };
+                Some(Err(error)) => {
+                    self.pending.push_back(Err(io_error(&error, &self.context)));
+                    self.finished = true;
                 }
-                _ => {}
-            },
-            Lexeme::PrefixedIri(prefixed_iri) => {
-                let iri =
-                    parse_prefixed_iri(Lexeme::PrefixedIri(prefixed_iri.to_string()), &context);
-
-                match iri {
-                    Some(iri) => match current_triple {
-                        (None, None, None) => {
-                            current_triple.0 = Some(iri);
-                        }
-                        (Some(_), None, None) => {
-                            current_triple.1 = Some(iri);
-                        }
-                        (Some(_), Some(_), None) => {
-                            current_triple.2 = Some(Object::Iri(iri.0));
-                        }
-                        _ => {}
-                    },
-                    _ => {
-                        println!("Error parsing prefixed iri: {}", prefixed_iri)
+                None => {
+                    if let Err(error) = finish(&mut self.context) {
+                        self.pending.push_back(Err(error));
                     }
+
+                    self.finished = true;
                 }
             }
-            Lexeme::Literal(literal) => {
-                current_triple.2 = Some(Object::Literal(literal.to_string()));
-            }
-            Lexeme::LangLiteral(literal, lang) => {
-                current_triple.2 = Some(Object::LangLiteral(literal.to_string(), lang.to_string()));
-            }
-            Lexeme::DataTypeLiteral(literal, datatype) => {
-                current_triple.2 = Some(Object::DataTypeLiteral(
-                    literal.to_string(),
-                    datatype.to_string(),
-                ));
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+fn parse_token(
+    token: &Token,
+    context: &mut ParseContext,
+    sink: &mut impl TripleSink,
+) -> Result<(), ParseError> {
+    context.last_span = token.span;
+
+    if let Lexeme::Comment(_) = token.lexeme {
+        return Ok(());
+    }
+
+    // `<g> {`, `[] {` and `GRAPH <g> {` name a graph with the subject that was just read
+    let label = std::mem::take(&mut context.label);
+    let starts_statement = context.frames.len() == 1
+        && matches!(context.statement(), Some(Statement { subject: None, .. }));
+    let mut anon = false; // the token closed a `[]` that started the statement
+
+    if context.graph_keyword {
+        let expected = match token.lexeme {
+            Lexeme::Iri(_)
+            | Lexeme::PrefixedIri(_)
+            | Lexeme::BlankNodeLabel(_)
+            | Lexeme::BlankNodeStartToken => !label,
+            Lexeme::BlankNodeEndToken => {
+                context.frames.len() == 2
+                    && matches!(
+                        context.statement(),
+                        Some(Statement {
+                            predicate: None,
+                            ..
+                        })
+                    )
             }
-            Lexeme::MultilineLiteral(multiline) => {
-                current_triple.2 = Some(Object::MultilineLiteral(multiline.to_string()));
+            Lexeme::GraphStartToken => label,
+            _ => false,
+        };
+
+        if !expected {
+            context.graph_keyword = false;
+            return Err(unexpected_token(token));
+        }
+    }
+
+    match &token.lexeme {
+        Lexeme::Iri(_) | Lexeme::PrefixedIri(_) => {
+            let iri = parse_iri(token, context)?;
+
+            match context.statement() {
+                Some(Statement {
+                    subject: Some(_),
+                    predicate: predicate @ None,
+                    ..
+                }) => {
+                    *predicate = Some(iri);
+                }
+                _ => context.add_term(Term::from(iri), token, sink)?,
             }
-            Lexeme::Prefix(key, value) => {
-                context.prefixes.insert(key.to_string(), value.to_string());
+        }
+        Lexeme::BlankNodeLabel(label) => {
+            let blank_node = context.blank_node(label);
+
+            context.add_term(Term::from(blank_node), token, sink)?;
+        }
+        Lexeme::BlankNodeStartToken => {
+            let blank_node = context.fresh_blank_node();
+
+            context.add_term(Term::from(blank_node.clone()), token, sink)?;
+            context
+                .frames
+                .push(Frame::Statement(Statement::with_subject(Subject::from(
+                    blank_node,
+                ))));
+        }
+        // `[]` or a property list that ends after an object or a `;`
+        Lexeme::BlankNodeEndToken => match (context.frames.len(), context.statement()) {
+            (2.., Some(statement))
+                if statement.object.is_some() || statement.predicate.is_none() =>
+            {
+                context.flush(sink);
+
+                let (properties, empty) = match context.frames.pop() {
+                    Some(Frame::Statement(statement)) => (
+                        statement.complete,
+                        !statement.complete && statement.predicate.is_none(),
+                    ),
+                    _ => (false, false),
+                };
+
+                anon = empty
+                    && context.frames.len() == 1
+                    && matches!(
+                        context.statement(),
+                        Some(Statement {
+                            predicate: None,
+                            ..
+                        })
+                    );
+
+                // `[ ex:p ex:o ] .` is a statement without predicates of its own
+                if let Some(
+                    statement @ Statement {
+                        predicate: None, ..
+                    },
+                ) = context.statement()
+                {
+                    statement.complete |= properties;
+                }
             }
-            Lexeme::Base(base) => {
-                context.base = Some(base.to_string());
+            _ => return Err(unexpected_token(token)),
+        },
+        Lexeme::CollectionStartToken => {
+            context
+                .frames
+                .push(Frame::Collection(Collection::default()));
+        }
+        Lexeme::CollectionEndToken => match context.frames.pop() {
+            Some(Frame::Collection(collection)) => {
+                let list = match (collection.head, collection.last) {
+                    (Some(head), Some(last)) => {
+                        let rest = Triple {
+                            subject: Subject::from(last),
+                            predicate: NamedNode::new(RDF_REST),
+                            object: Term::from(NamedNode::new(RDF_NIL)),
+                        };
+
+                        context.emit(rest, sink);
+
+                        Term::from(head)
+                    }
+                    _ => Term::from(NamedNode::new(RDF_NIL)),
+                };
+
+                context.add_term(list, token, sink)?;
             }
-            Lexeme::A => {
-                if let (Some(_), None, None) = current_triple {
-                    current_triple.1 = Some(Iri(
-                        "http://www.w3.org/1999/02/22-rdf-syntax-ns#type".to_string()
-                    ));
-                }
+            _ => return Err(unexpected_token(token)),
+        },
+        Lexeme::Literal(_)
+        | Lexeme::LangLiteral(_, _)
+        | Lexeme::DataTypeLiteral(_, _)
+        | Lexeme::PrefixedDataTypeLiteral(_, _)
+        | Lexeme::MultilineLiteral(_)
+        | Lexeme::Unknown(_) => {
+            let literal = parse_literal(token, context)?;
+
+            context.add_term(Term::from(literal), token, sink)?;
+        }
+        Lexeme::Prefix(_, _) | Lexeme::Base(_) if context.in_graph => {
+            return Err(unexpected_token(token))
+        }
+        Lexeme::Prefix(key, value) => {
+            let value = resolve_iri(value, token, context)?;
+
+            sink.prefix(key, &value);
+            context.prefixes.insert(key.to_string(), value);
+        }
+        Lexeme::Base(base) => {
+            // a relative base is resolved against the previous one
+            let base = resolve_iri(base, token, context)?;
+
+            sink.base(&base);
+            context.base = Some(base);
+        }
+        Lexeme::A => match context.statement() {
+            Some(Statement {
+                subject: Some(_),
+                predicate: predicate @ None,
+                ..
+            }) => {
+                *predicate = Some(NamedNode::new(RDF_TYPE));
             }
-            Lexeme::ObjectListToken => {
-                if let (Some(subject), Some(predicate), Some(object)) = current_triple {
-                    triples.push(Triple {
-                        subject: subject.clone(),
-                        predicate: predicate.clone(),
-                        object,
-                    });
-                    current_triple = (Some(subject), Some(predicate), None);
+            _ => return Err(unexpected_token(token)),
+        },
+        Lexeme::ObjectListToken => match context.statement() {
+            Some(Statement {
+                object: Some(_), ..
+            }) => context.flush(sink),
+            _ => return Err(unexpected_token(token)),
+        },
+        Lexeme::PredicateListToken => match context.statement() {
+            Some(statement) if statement.can_end() => {
+                let triple = statement.flush();
+
+                statement.predicate = None;
+
+                if let Some(triple) = triple {
+                    context.emit(triple, sink);
                 }
             }
-            Lexeme::PredicateListToken => {
-                if let (Some(subject), Some(predicate), Some(object)) = current_triple {
-                    triples.push(Triple {
-                        subject: subject.clone(),
-                        predicate: predicate.clone(),
-                        object: object,
-                    });
-
-                    context.subject = Some(subject.to_owned());
-                    current_triple = (Some(subject), None, None);
-                }
+            _ => return Err(unexpected_token(token)),
+        },
+        Lexeme::EndToken => {
+            if context.frames.len() > 1 || !context.statement_can_end() {
+                return Err(unexpected_token(token));
             }
-            Lexeme::EndToken => {
-                match current_triple {
-                    (Some(subject), Some(predicate), Some(object)) => {
-                        triples.push(Triple {
-                            subject,
-                            predicate,
-                            object,
-                        });
-                    }
-                    _ => {}
-                }
 
-                context.subject = None;
-                current_triple = (None, None, None);
-                is_incomplete_triple = false;
+            context.flush(sink);
+            context.frames[0] = Frame::Statement(Statement::default());
+        }
+        Lexeme::GraphStartToken => {
+            if !context.trig || context.in_graph || context.frames.len() > 1 {
+                return Err(unexpected_token(token));
             }
-            Lexeme::Unknown(token) => match token.as_str() {
-                "true" => {
-                    current_triple.2 = Some(Object::Boolean(true));
-                }
-                "false" => {
-                    current_triple.2 = Some(Object::Boolean(false));
-                }
-                token => {
-                    if is_integer(token) {
-                        let test = token.parse::<i32>();
-
-                        match test {
-                            Ok(integer) => {
-                                current_triple.2 = Some(Object::Integer(integer));
-                            }
-                            Err(_) => {
-                                println!("Error parsing integer: {}", token);
-                            }
-                        }
-                    } else if is_decimal(token) {
-                        let test = token.parse::<f32>();
-
-                        match test {
-                            Ok(integer) => {
-                                current_triple.2 = Some(Object::Decimal(integer));
-                            }
-                            Err(_) => {
-                                println!("Error parsing decimal: {}", token);
-                            }
-                        }
-                    } else if is_double(token) {
-                        let test = token.parse::<f64>();
-
-                        match test {
-                            Ok(integer) => {
-                                current_triple.2 = Some(Object::Double(integer));
-                            }
-                            Err(_) => {
-                                println!("Error parsing double: {}", token);
-                            }
-                        }
-                    } else {
-                        println!("Unknown token: {}", token);
-                    }
-                }
-                _ => {
-                    println!("Unknown token: {}", token);
-                }
-            },
-            Lexeme::Comment(_) => {}
+
+            let graph_keyword = context.graph_keyword;
+            let graph = match context.statement() {
+                Some(Statement { subject: None, .. }) if !graph_keyword => None,
+                Some(Statement {
+                    subject: Some(subject),
+                    predicate: None,
+                    ..
+                }) if label => Some(GraphName::from(subject.clone())),
+                _ => return Err(unexpected_token(token)),
+            };
+
+            context.frames[0] = Frame::Statement(Statement::default());
+            context.in_graph = true;
+            context.graph = graph;
+            context.graph_keyword = false;
         }
-    }
+        Lexeme::GraphEndToken => {
+            if !context.in_graph || context.frames.len() > 1 || !context.statement_can_end() {
+                return Err(unexpected_token(token));
+            }
 
-    // pass subject to next line
-    if is_incomplete_triple {
-        match current_triple {
-            (Some(subject), None, None) => {
-                context.subject = Some(subject);
+            // the last statement of a block doesn't need a `.`
+            context.flush(sink);
+            context.frames[0] = Frame::Statement(Statement::default());
+            context.in_graph = false;
+            context.graph = None;
+        }
+        Lexeme::Graph => {
+            if !context.trig || context.in_graph || !starts_statement {
+                return Err(unexpected_token(token));
             }
-            _ => {}
+
+            context.graph_keyword = true;
         }
+        // variables and operators are only allowed in queries
+        Lexeme::Variable(_) | Lexeme::Operator(_) => return Err(unexpected_token(token)),
+        Lexeme::Comment(_) => {}
     }
 
-    triples
-}
+    context.label = context.trig
+        && (anon
+            || starts_statement
+                && matches!(
+                    token.lexeme,
+                    Lexeme::Iri(_) | Lexeme::PrefixedIri(_) | Lexeme::BlankNodeLabel(_)
+                ));
 
-fn is_integer(token: &str) -> bool {
-    token.parse::<i32>().is_ok()
+    Ok(())
 }
 
-fn is_decimal(token: &str) -> bool {
-    let has_mantissa = token.contains('E') || token.contains('e');
+// input the lexer skipped instead of emitting a token
+pub fn syntax_error(diagnostic: Diagnostic) -> ParseError {
+    ParseError::InvalidSyntax {
+        message: diagnostic.message,
+        span: diagnostic.span,
+    }
+}
 
-    token.parse::<f32>().is_ok() && !has_mantissa
+fn io_error(error: &io::Error, context: &ParseContext) -> ParseError {
+    ParseError::Io {
+        message: error.to_string(),
+        span: context.last_span,
+    }
 }
 
-fn is_double(token: &str) -> bool {
-    token.parse::<f64>().is_ok()
+pub fn unexpected_token(token: &Token) -> ParseError {
+    ParseError::UnexpectedToken {
+        token: token.lexeme.to_string(),
+        span: token.span,
+    }
 }
 
-pub fn parse_iri(lexeme: &Lexeme, context: &ParseContext) -> Option<Iri> {
-    match lexeme {
-        Lexeme::Iri(iri) => match is_relative_iri(&iri.to_string()) {
-            true => match &context.base {
-                Some(base) => {
-                    let mut full_iri = base.clone();
+// strings, booleans and numbers
+pub fn parse_literal(token: &Token, context: &ParseContext) -> Result<Literal, ParseError> {
+    match &token.lexeme {
+        Lexeme::Literal(literal) | Lexeme::MultilineLiteral(literal) => {
+            Ok(Literal::new_simple(literal))
+        }
+        Lexeme::LangLiteral(literal, lang) => Ok(Literal::new_language_tagged(literal, lang)),
+        Lexeme::DataTypeLiteral(literal, datatype) => {
+            let datatype = resolve_iri(datatype, token, context)?;
 
-                    full_iri.push_str(&iri);
+            Ok(Literal::new_typed(literal, datatype))
+        }
+        Lexeme::PrefixedDataTypeLiteral(literal, datatype) => {
+            let NamedNode(datatype) = parse_prefixed_iri(datatype, token, context)?;
 
-                    Some(Iri(full_iri))
-                }
-                None => None,
-            },
-            false => Some(Iri(iri.to_string())),
-        },
-        Lexeme::PrefixedIri(prefixed_iri) => {
-            parse_prefixed_iri(Lexeme::PrefixedIri(prefixed_iri.to_string()), &context)
+            Ok(Literal::new_typed(literal, datatype))
         }
-        _ => None,
+        Lexeme::Unknown(unknown) => parse_unknown(unknown, token.span),
+        _ => Err(unexpected_token(token)),
     }
 }
 
-fn is_relative_iri(iri: &str) -> bool {
-    let is_relative = match iri.parse::<Uri>() {
-        Ok(uri) => uri.scheme().is_none(),
-        Err(_) => return false,
-    };
+// booleans and numbers are lexed as unknown tokens
+fn parse_unknown(token: &str, span: Span) -> Result<Literal, ParseError> {
+    match token {
+        "true" | "false" => return Ok(Literal::new_typed(token, XSD_BOOLEAN)),
+        token if token.starts_with(['"', '\'']) && !is_closed_literal(token) => {
+            return Err(ParseError::UnterminatedLiteral {
+                literal: token.to_string(),
+                span,
+            })
+        }
+        // a literal with a broken language tag or datatype
+        token if token.starts_with(['"', '\'']) => {
+            return Err(ParseError::UnexpectedToken {
+                token: token.to_string(),
+                span,
+            })
+        }
+        _ => {}
+    }
+
+    if number::is_integer(token) {
+        return Ok(Literal::new_typed(token, XSD_INTEGER));
+    }
+
+    if number::is_decimal(token) {
+        return Ok(Literal::new_typed(token, XSD_DECIMAL));
+    }
 
-    is_relative
+    match number::is_double(token) {
+        true => Ok(Literal::new_typed(token, XSD_DOUBLE)),
+        false => Err(ParseError::UnexpectedToken {
+            token: token.to_string(),
+            span,
+        }),
+    }
+}
+
+// whether the opening quote of a literal has a matching unescaped closing quote
+fn is_closed_literal(token: &str) -> bool {
+    let mut chars = token.chars();
+    let quote = chars.next();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            c if Some(c) == quote => return true,
+            _ => {}
+        }
+    }
+
+    false
 }
 
-fn parse_prefixed_iri(lexeme: Lexeme, context: &ParseContext) -> Option<Iri> {
-    match lexeme {
-        Lexeme::PrefixedIri(ref prefixed_iri) => {
-            let parts = prefixed_iri.split(':').collect::<Vec<&str>>();
+pub fn parse_iri(token: &Token, context: &ParseContext) -> Result<NamedNode, ParseError> {
+    match &token.lexeme {
+        Lexeme::Iri(iri) => resolve_iri(iri, token, context).map(NamedNode),
+        Lexeme::PrefixedIri(prefixed_iri) => parse_prefixed_iri(prefixed_iri, token, context),
+        _ => Err(unexpected_token(token)),
+    }
+}
 
-            let (prefix, relative_iri) = (parts.first(), parts.get(1));
+// resolves a relative iri against the current base
+fn resolve_iri(iri: &str, token: &Token, context: &ParseContext) -> Result<String, ParseError> {
+    match (iri::is_absolute(iri), &context.base) {
+        (true, _) => Ok(iri.to_string()),
+        (false, Some(base)) => Ok(iri::resolve(base, iri)),
+        (false, None) => Err(ParseError::RelativeIriWithoutBase {
+            iri: iri.to_string(),
+            span: token.span,
+        }),
+    }
+}
 
-            match (prefix, relative_iri) {
-                (Some(prefix), Some(relative_iri)) => {
-                    let iri_prefix = format!("{}:", prefix);
+fn parse_prefixed_iri(
+    prefixed_iri: &str,
+    token: &Token,
+    context: &ParseContext,
+) -> Result<NamedNode, ParseError> {
+    let (prefix, relative_iri) = prefixed_iri
+        .split_once(':')
+        .ok_or_else(|| unexpected_token(token))?;
+    let iri_prefix = format!("{}:", prefix);
 
-                    match context.prefixes.get(&iri_prefix) {
-                        Some(prefix) => {
-                            let mut prefix = prefix.to_string();
+    match context.prefixes.get(&iri_prefix) {
+        Some(prefix) => {
+            let mut prefix = prefix.to_string();
 
-                            prefix.push_str(relative_iri);
+            prefix.push_str(relative_iri);
 
-                            Some(Iri(prefix))
-                        }
-                        None => None,
-                    }
-                }
-                _ => None,
-            }
+            Ok(NamedNode(prefix))
         }
-        _ => None,
+        None => Err(ParseError::UndefinedPrefix {
+            prefix: iri_prefix,
+            span: token.span,
+        }),
     }
 }
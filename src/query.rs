@@ -0,0 +1,297 @@
+// basic graph patterns, conjunctive queries like `?p a foaf:Person . ?p foaf:name ?n` over a graph
+use std::collections::{BTreeMap, HashSet};
+
+use crate::graph::Graph;
+use crate::lexer::{self, Lexeme, LexerContext, Token};
+use crate::parser::{self, ParseContext, ParseError};
+use crate::term::*;
+
+// the terms that the variables of one solution are bound to
+pub type Bindings = BTreeMap<String, Term>;
+
+// subject, predicate and object of a graph lookup, None matches any term
+type Lookup = (Option<Subject>, Option<NamedNode>, Option<Term>);
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum TermPattern {
+    Variable(String), // without the `?`
+    Term(Term),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TriplePattern {
+    pub subject: TermPattern,
+    pub predicate: TermPattern,
+    pub object: TermPattern,
+}
+
+impl TriplePattern {
+    fn variables(&self) -> impl Iterator<Item = &str> {
+        [&self.subject, &self.predicate, &self.object]
+            .into_iter()
+            .filter_map(|pattern| match pattern {
+                TermPattern::Variable(name) => Some(name.as_str()),
+                TermPattern::Term(_) => None,
+            })
+    }
+
+    // the triples matching the pattern with the variables substituted that are bound already
+    fn triples(&self, graph: &Graph, bindings: &Bindings) -> Vec<Triple> {
+        let bound = |pattern: &TermPattern| match pattern {
+            TermPattern::Variable(name) => bindings.get(name).cloned(),
+            TermPattern::Term(term) => Some(term.clone()),
+        };
+
+        match self.lookup(bound) {
+            Some((subject, predicate, object)) => graph
+                .triples_matching(subject.as_ref(), predicate.as_ref(), object.as_ref())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // the solutions of the pattern that are compatible with the given bindings
+    fn solutions(&self, graph: &Graph, bindings: &Bindings) -> Vec<Bindings> {
+        self.triples(graph, bindings)
+            .into_iter()
+            .filter_map(|triple| {
+                let mut bindings = bindings.clone();
+                let terms = [
+                    (&self.subject, Term::from(triple.subject)),
+                    (&self.predicate, Term::from(triple.predicate)),
+                    (&self.object, triple.object),
+                ];
+
+                for (pattern, term) in terms {
+                    if let TermPattern::Variable(name) = pattern {
+                        // a variable used twice in a pattern has to match the same term
+                        match bindings.get(name) {
+                            Some(bound) if *bound != term => return None,
+                            Some(_) => {}
+                            None => {
+                                bindings.insert(name.clone(), term);
+                            }
+                        }
+                    }
+                }
+
+                Some(bindings)
+            })
+            .collect()
+    }
+
+    // the number of triples matching the terms of the pattern, ignoring its variables
+    fn selectivity(&self, graph: &Graph) -> usize {
+        let term = |pattern: &TermPattern| match pattern {
+            TermPattern::Variable(_) => None,
+            TermPattern::Term(term) => Some(term.clone()),
+        };
+
+        match self.lookup(term) {
+            Some((subject, predicate, object)) => {
+                graph.count_matching(subject.as_ref(), predicate.as_ref(), object.as_ref())
+            }
+            None => 0,
+        }
+    }
+
+    // None if a term is in a position where it can't occur
+    fn lookup(&self, term: impl Fn(&TermPattern) -> Option<Term>) -> Option<Lookup> {
+        let subject = match term(&self.subject).map(Subject::try_from) {
+            Some(Err(_)) => return None,
+            subject => subject.map(Result::unwrap),
+        };
+        let predicate = match term(&self.predicate) {
+            Some(Term::NamedNode(predicate)) => Some(predicate),
+            Some(_) => return None,
+            None => None,
+        };
+
+        Some((subject, predicate, term(&self.object)))
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct BasicGraphPattern {
+    pub patterns: Vec<TriplePattern>,
+}
+
+impl BasicGraphPattern {
+    // turtle like triples with `?variables`, prefixes are taken from and can be added to the context
+    pub fn parse(text: &str, context: &mut ParseContext) -> Result<BasicGraphPattern, ParseError> {
+        BasicGraphPattern::from_tokens(&tokenize_query(text)?, context)
+    }
+
+    // `;` and `,` lists are supported, blank nodes and collections are not
+    pub fn from_tokens(
+        tokens: &[Token],
+        context: &mut ParseContext,
+    ) -> Result<BasicGraphPattern, ParseError> {
+        let mut patterns = Vec::new();
+        let mut subject: Option<TermPattern> = None;
+        let mut predicate: Option<TermPattern> = None;
+        let mut object = false; // the current predicate has an object
+
+        for token in tokens {
+            match &token.lexeme {
+                Lexeme::Comment(_) => {}
+                Lexeme::Prefix(_, _) | Lexeme::Base(_) if subject.is_none() => {
+                    parser::parse(std::slice::from_ref(token), context)?;
+                }
+                Lexeme::EndToken if object || subject.is_none() => {
+                    subject = None;
+                    predicate = None;
+                    object = false;
+                }
+                Lexeme::PredicateListToken if object => {
+                    predicate = None;
+                    object = false;
+                }
+                Lexeme::ObjectListToken if object => object = false,
+                Lexeme::A if subject.is_some() && predicate.is_none() => {
+                    predicate = Some(TermPattern::Term(Term::from(NamedNode::new(RDF_TYPE))));
+                }
+                _ => {
+                    let term = term_pattern(token, context)?;
+
+                    match (&subject, &predicate, term) {
+                        // literals can't be subjects, as in turtle
+                        (
+                            None,
+                            _,
+                            term @ (TermPattern::Variable(_)
+                            | TermPattern::Term(Term::NamedNode(_))),
+                        ) => subject = Some(term),
+                        (
+                            Some(_),
+                            None,
+                            term @ (TermPattern::Variable(_)
+                            | TermPattern::Term(Term::NamedNode(_))),
+                        ) => predicate = Some(term),
+                        (Some(subject), Some(predicate), term) if !object => {
+                            patterns.push(TriplePattern {
+                                subject: subject.clone(),
+                                predicate: predicate.clone(),
+                                object: term,
+                            });
+                            object = true;
+                        }
+                        _ => return Err(parser::unexpected_token(token)),
+                    }
+                }
+            }
+        }
+
+        // the last pattern doesn't need a `.`
+        match (subject, object, tokens.last()) {
+            (Some(_), false, Some(token)) => {
+                Err(ParseError::UnexpectedEndOfInput { span: token.span })
+            }
+            _ => Ok(BasicGraphPattern { patterns }),
+        }
+    }
+
+    // in order of their first appearance
+    pub fn variables(&self) -> Vec<String> {
+        let mut variables: Vec<String> = Vec::new();
+
+        for variable in self.patterns.iter().flat_map(TriplePattern::variables) {
+            if !variables.iter().any(|known| known == variable) {
+                variables.push(variable.to_string());
+            }
+        }
+
+        variables
+    }
+
+    // the bindings of all solutions, an empty pattern has a single empty solution
+    pub fn evaluate(&self, graph: &Graph) -> Vec<Bindings> {
+        self.evaluate_with(graph, vec![Bindings::new()])
+    }
+
+    // the solutions that extend the input solutions, their variables are fixed to the bound terms,
+    // the join order is planned once for all of them
+    pub fn evaluate_with(&self, graph: &Graph, input: Vec<Bindings>) -> Vec<Bindings> {
+        let plan = self.plan(graph, &input);
+        let mut solutions = input;
+
+        for pattern in plan {
+            solutions = solutions
+                .iter()
+                .flat_map(|bindings| pattern.solutions(graph, bindings))
+                .collect();
+
+            if solutions.is_empty() {
+                break;
+            }
+        }
+
+        solutions
+    }
+
+    // the join order, patterns that share a variable with the ones before them or with the
+    // input solutions and match the fewest triples go first
+    fn plan(&self, graph: &Graph, input: &[Bindings]) -> Vec<&TriplePattern> {
+        let mut remaining: Vec<(&TriplePattern, usize)> = self
+            .patterns
+            .iter()
+            .map(|pattern| (pattern, pattern.selectivity(graph)))
+            .collect();
+        let mut bound: HashSet<&str> = input
+            .iter()
+            .flat_map(Bindings::keys)
+            .map(String::as_str)
+            .collect();
+        let mut plan = Vec::with_capacity(remaining.len());
+
+        while let Some(next) = (0..remaining.len()).min_by_key(|&i| {
+            let (pattern, count) = remaining[i];
+            let joins = bound.is_empty()
+                || pattern.variables().next().is_none()
+                || pattern.variables().any(|variable| bound.contains(variable));
+
+            (!joins, count)
+        }) {
+            let (pattern, _) = remaining.remove(next);
+
+            bound.extend(pattern.variables());
+            plan.push(pattern);
+        }
+
+        plan
+    }
+}
+
+// the tokens of a query text, without comments
+pub fn tokenize_query(text: &str) -> Result<Vec<Token>, ParseError> {
+    let mut lexer_context = LexerContext::new();
+    let mut tokens: Vec<Token> = Vec::new();
+
+    lexer_context.query = true;
+
+    for (line_num, line) in (1..).zip(text.lines()) {
+        tokens.append(&mut lexer::tokenize(line, line_num, &mut lexer_context));
+    }
+
+    lexer::finish(&mut lexer_context);
+
+    if let Some(diagnostic) = lexer_context.diagnostics.into_iter().next() {
+        return Err(parser::syntax_error(diagnostic));
+    }
+
+    tokens.retain(|token| !matches!(token.lexeme, Lexeme::Comment(_)));
+
+    Ok(tokens)
+}
+
+fn term_pattern(token: &Token, context: &ParseContext) -> Result<TermPattern, ParseError> {
+    match &token.lexeme {
+        Lexeme::Variable(name) => Ok(TermPattern::Variable(name.clone())),
+        Lexeme::Iri(_) | Lexeme::PrefixedIri(_) => Ok(TermPattern::Term(Term::from(
+            parser::parse_iri(token, context)?,
+        ))),
+        _ => Ok(TermPattern::Term(Term::from(parser::parse_literal(
+            token, context,
+        )?))),
+    }
+}
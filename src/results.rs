@@ -0,0 +1,223 @@
+// sparql 1.1 query results as json, csv and tsv
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::query::Bindings;
+use crate::sparql::QueryResults;
+use crate::term::*;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ResultsFormat {
+    Json,
+    Csv,
+    Tsv,
+}
+
+impl ResultsFormat {
+    pub fn from_name(name: &str) -> Option<ResultsFormat> {
+        match name {
+            "json" => Some(ResultsFormat::Json),
+            "csv" => Some(ResultsFormat::Csv),
+            "tsv" => Some(ResultsFormat::Tsv),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ResultsFormat::Json => "application/sparql-results+json",
+            ResultsFormat::Csv => "text/csv; charset=utf-8",
+            ResultsFormat::Tsv => "text/tab-separated-values; charset=utf-8",
+        }
+    }
+}
+
+pub fn write_results<W: Write>(
+    out: &mut W,
+    results: &QueryResults,
+    format: ResultsFormat,
+) -> io::Result<()> {
+    match format {
+        ResultsFormat::Json => write_json(out, results),
+        ResultsFormat::Csv => write_csv(out, results),
+        ResultsFormat::Tsv => write_tsv(out, results),
+    }
+}
+
+pub fn to_string(results: &QueryResults, format: ResultsFormat) -> String {
+    let mut out = Vec::new();
+
+    write_results(&mut out, results, format).expect("writing to a vec");
+    String::from_utf8(out).expect("results are utf-8")
+}
+
+// one binding per line, see SPARQL 1.1 Query Results JSON Format
+fn write_json<W: Write>(out: &mut W, results: &QueryResults) -> io::Result<()> {
+    let (variables, solutions) = match results {
+        QueryResults::Boolean(value) => {
+            return writeln!(out, "{{\"head\":{{}},\"boolean\":{}}}", value)
+        }
+        QueryResults::Solutions {
+            variables,
+            solutions,
+        } => (variables, solutions),
+    };
+
+    let variables: Vec<String> = variables.iter().map(|v| json_string(v)).collect();
+
+    writeln!(out, "{{\"head\":{{\"vars\":[{}]}},", variables.join(","))?;
+    write!(out, "\"results\":{{\"bindings\":[")?;
+
+    for (i, bindings) in solutions.iter().enumerate() {
+        let separator = match i {
+            0 => "\n",
+            _ => ",\n",
+        };
+
+        write!(out, "{}{}", separator, json_bindings(bindings))?;
+    }
+
+    writeln!(out, "\n]}}}}")
+}
+
+fn json_bindings(bindings: &Bindings) -> String {
+    let bindings: Vec<String> = bindings
+        .iter()
+        .map(|(variable, term)| format!("{}:{}", json_string(variable), json_term(term)))
+        .collect();
+
+    format!("{{{}}}", bindings.join(","))
+}
+
+fn json_term(term: &Term) -> String {
+    match term {
+        Term::NamedNode(named_node) => {
+            format!(
+                "{{\"type\":\"uri\",\"value\":{}}}",
+                json_string(named_node.as_str())
+            )
+        }
+        Term::BlankNode(blank_node) => {
+            format!(
+                "{{\"type\":\"bnode\",\"value\":{}}}",
+                json_string(blank_node.as_str())
+            )
+        }
+        Term::Literal(literal) => {
+            let mut json = format!(
+                "{{\"type\":\"literal\",\"value\":{}",
+                json_string(&literal.value)
+            );
+
+            // xsd:string is the default and left out like in n-triples
+            match &literal.language {
+                Some(language) => {
+                    let _ = write!(json, ",\"xml:lang\":{}", json_string(language));
+                }
+                None if literal.is_plain() => {}
+                None => {
+                    let _ = write!(
+                        json,
+                        ",\"datatype\":{}",
+                        json_string(literal.datatype.as_str())
+                    );
+                }
+            }
+
+            json + "}"
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+// plain values without datatypes or language tags, lines end with CRLF
+fn write_csv<W: Write>(out: &mut W, results: &QueryResults) -> io::Result<()> {
+    let (variables, solutions) = match results {
+        QueryResults::Boolean(value) => return write!(out, "{}\r\n", value),
+        QueryResults::Solutions {
+            variables,
+            solutions,
+        } => (variables, solutions),
+    };
+
+    let header: Vec<String> = variables.iter().map(|v| csv_field(v)).collect();
+
+    write!(out, "{}\r\n", header.join(","))?;
+
+    for bindings in solutions {
+        let row: Vec<String> = variables
+            .iter()
+            .map(|variable| match bindings.get(variable) {
+                Some(Term::NamedNode(named_node)) => csv_field(named_node.as_str()),
+                Some(Term::BlankNode(blank_node)) => {
+                    csv_field(&format!("_:{}", blank_node.as_str()))
+                }
+                Some(Term::Literal(literal)) => csv_field(&literal.value),
+                None => String::new(),
+            })
+            .collect();
+
+        write!(out, "{}\r\n", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+// quoted if it contains a quote, comma or line break
+fn csv_field(value: &str) -> String {
+    match value.contains(['"', ',', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+// terms in their n-triples form, unbound variables are empty
+fn write_tsv<W: Write>(out: &mut W, results: &QueryResults) -> io::Result<()> {
+    let (variables, solutions) = match results {
+        QueryResults::Boolean(value) => return writeln!(out, "{}", value),
+        QueryResults::Solutions {
+            variables,
+            solutions,
+        } => (variables, solutions),
+    };
+
+    let header: Vec<String> = variables.iter().map(|v| format!("?{}", v)).collect();
+
+    writeln!(out, "{}", header.join("\t"))?;
+
+    for bindings in solutions {
+        let row: Vec<String> = variables
+            .iter()
+            .map(|variable| match bindings.get(variable) {
+                Some(term) => term.to_string(),
+                None => String::new(),
+            })
+            .collect();
+
+        writeln!(out, "{}", row.join("\t"))?;
+    }
+
+    Ok(())
+}
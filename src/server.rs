@@ -0,0 +1,583 @@
+// a local stand-in for a triplestore, the SPARQL 1.1 Protocol at `/sparql` and the
+// Graph Store Protocol at `/data` over an in-memory store, one request per connection
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::graph::Graph;
+use crate::iri;
+use crate::ntriples::NTriplesParser;
+use crate::parser::{self, ParseContext, ParseError};
+use crate::results::{self, ResultsFormat};
+use crate::sparql::Query;
+use crate::term::*;
+use crate::turtle;
+
+const TURTLE: &str = "text/turtle";
+const N_TRIPLES: &str = "application/n-triples";
+const SPARQL_QUERY: &str = "application/sparql-query";
+const FORM: &str = "application/x-www-form-urlencoded";
+
+// larger request lines and headers, and larger bodies, are rejected before they are read
+const MAX_HEAD: usize = 64 * 1024;
+const MAX_BODY: usize = 64 * 1024 * 1024;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub parameters: Vec<(String, String)>, // decoded query string
+    pub headers: Vec<(String, String)>,    // lowercase names
+    pub body: String,
+}
+
+impl Request {
+    // reads a single http/1.1 request, malformed ones are InvalidData errors, see error_status
+    pub fn read(reader: &mut impl BufRead) -> io::Result<Request> {
+        let mut line = String::new();
+        let mut head = 0; // bytes of the request line and headers read so far
+
+        if read_head_line(reader, &mut line, &mut head)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let mut parts = line.split_whitespace();
+        let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+                (method.to_string(), target.to_string())
+            }
+            _ => return Err(invalid_request(400, "malformed request line")),
+        };
+        let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+        let mut request = Request {
+            method,
+            path: decode(path, false),
+            parameters: decode_form(query),
+            headers: Vec::new(),
+            body: String::new(),
+        };
+
+        loop {
+            read_head_line(reader, &mut line, &mut head)?;
+
+            let header = line.trim_end_matches(['\r', '\n']);
+
+            if header.is_empty() {
+                break;
+            }
+
+            match header.split_once(':') {
+                Some((name, value)) => request
+                    .headers
+                    .push((name.trim().to_ascii_lowercase(), value.trim().to_string())),
+                None => return Err(invalid_request(400, "malformed header")),
+            }
+        }
+
+        if request.header("transfer-encoding").is_some() {
+            return Err(invalid_request(400, "chunked bodies are not supported"));
+        }
+
+        let length = match request.header("content-length").map(str::parse::<usize>) {
+            Some(Ok(length)) if length <= MAX_BODY => length,
+            Some(Ok(_)) => return Err(invalid_request(413, "the body is too large")),
+            Some(_) => return Err(invalid_request(400, "invalid content-length")),
+            None => 0,
+        };
+        // read as it arrives, a content-length alone doesn't allocate the whole body
+        let mut body = Vec::new();
+
+        match reader.by_ref().take(length as u64).read_to_end(&mut body)? == length {
+            true => (),
+            false => return Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+        request.body =
+            String::from_utf8(body).map_err(|_| invalid_request(400, "the body is not utf-8"))?;
+
+        Ok(request)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(parameter, _)| parameter == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // the media type of the body without parameters like charset
+    fn content_type(&self) -> Option<String> {
+        self.header("content-type").map(media_type)
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn new(status: u16, content_type: &str, body: String) -> Response {
+        Response {
+            status,
+            headers: vec![("Content-Type".to_string(), content_type.to_string())],
+            body,
+        }
+    }
+
+    pub fn no_content(status: u16) -> Response {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    // a plain text message
+    pub fn error(status: u16, message: impl Into<String>) -> Response {
+        Response::new(status, "text/plain; charset=utf-8", message.into() + "\n")
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn write<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        self.write_head(out)?;
+        out.write_all(self.body.as_bytes())?;
+        out.flush()
+    }
+
+    // the status line and headers without the body, the answer to a HEAD request
+    pub fn write_head<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(out, "HTTP/1.1 {} {}\r\n", self.status, reason(self.status))?;
+
+        for (name, value) in &self.headers {
+            write!(out, "{}: {}\r\n", name, value)?;
+        }
+
+        write!(
+            out,
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.body.len()
+        )?;
+        out.flush()
+    }
+}
+
+// the default graph holds the served files, named graphs are only created through `/data`
+pub struct Server {
+    default: Graph,
+    named: BTreeMap<NamedNode, Graph>,
+    prefixes: HashMap<String, String>, // usable in queries and used to write turtle
+}
+
+impl Server {
+    pub fn new(default: Graph, prefixes: HashMap<String, String>) -> Server {
+        Server {
+            default,
+            named: BTreeMap::new(),
+            prefixes,
+        }
+    }
+
+    pub fn handle(&mut self, request: &Request) -> Response {
+        match request.path.as_str() {
+            "/sparql" => self.query(request),
+            "/data" => self.graph_store(request),
+            path => Response::error(404, format!("no endpoint at {}", path)),
+        }
+    }
+
+    // queries run over the default graph
+    fn query(&self, request: &Request) -> Response {
+        let text = match (request.method.as_str(), request.content_type().as_deref()) {
+            ("GET", _) => request.parameter("query").map(str::to_string),
+            ("POST", Some(SPARQL_QUERY)) => Some(request.body.clone()),
+            ("POST", Some(FORM)) => decode_form(&request.body)
+                .into_iter()
+                .find(|(name, _)| name == "query")
+                .map(|(_, query)| query),
+            ("POST", _) => return Response::error(415, "expected a query or form body"),
+            _ => return method_not_allowed("GET, POST"),
+        };
+        let text = match text {
+            Some(text) => text,
+            None => return Response::error(400, "missing query"),
+        };
+
+        let offered = [
+            "application/sparql-results+json",
+            "application/json",
+            "text/csv",
+            "text/tab-separated-values",
+        ];
+        let format = match negotiate(request.header("accept"), &offered) {
+            Some("text/csv") => ResultsFormat::Csv,
+            Some("text/tab-separated-values") => ResultsFormat::Tsv,
+            Some(_) => ResultsFormat::Json,
+            None => return Response::error(406, format!("results are {}", offered.join(", "))),
+        };
+
+        let mut context = ParseContext::new();
+
+        context.prefixes = self.prefixes.clone();
+
+        match Query::parse(&text, &mut context) {
+            Ok(query) => Response::new(
+                200,
+                format.content_type(),
+                results::to_string(&query.evaluate(&self.default), format),
+            ),
+            Err(error) => Response::error(400, format!("invalid query: {}", error)),
+        }
+    }
+
+    // `?default` or `?graph=<iri>` select the graph
+    fn graph_store(&mut self, request: &Request) -> Response {
+        let name = match (request.parameter("default"), request.parameter("graph")) {
+            (Some(_), None) => None,
+            (None, Some(name)) if iri::is_absolute(name) => Some(NamedNode::new(name)),
+            (None, Some(name)) => {
+                return Response::error(400, format!("graph `{}` is not an absolute iri", name))
+            }
+            _ => return Response::error(400, "expected either ?default or ?graph=<iri>"),
+        };
+        let exists = name
+            .as_ref()
+            .is_none_or(|name| self.named.contains_key(name));
+
+        match request.method.as_str() {
+            "GET" | "HEAD" if !exists => Response::error(404, "no such graph"),
+            // serve leaves out the body of HEAD responses but keeps its length
+            "GET" | "HEAD" => self.get_graph(request, name.as_ref()),
+            "PUT" | "POST" => {
+                let triples = match parse_body(request, name.as_ref()) {
+                    Ok(triples) => triples,
+                    Err(response) => return response,
+                };
+                let graph = match &name {
+                    Some(name) => self.named.entry(name.clone()).or_default(),
+                    None => &mut self.default,
+                };
+
+                // PUT replaces the graph, POST adds to it
+                if request.method == "PUT" {
+                    *graph = Graph::new();
+                }
+
+                graph.extend(triples);
+
+                match exists {
+                    true => Response::no_content(204),
+                    false => Response::no_content(201),
+                }
+            }
+            "DELETE" => match &name {
+                Some(name) if self.named.remove(name).is_none() => {
+                    Response::error(404, "no such graph")
+                }
+                Some(_) => Response::no_content(204),
+                None => {
+                    self.default = Graph::new();
+                    Response::no_content(204)
+                }
+            },
+            _ => method_not_allowed("GET, HEAD, PUT, POST, DELETE"),
+        }
+    }
+
+    fn get_graph(&self, request: &Request, name: Option<&NamedNode>) -> Response {
+        let graph = match name {
+            Some(name) => &self.named[name],
+            None => &self.default,
+        };
+        let triples: Vec<Triple> = graph.iter().collect();
+
+        match negotiate(request.header("accept"), &[TURTLE, N_TRIPLES]) {
+            Some(TURTLE) => Response::new(
+                200,
+                "text/turtle; charset=utf-8",
+                turtle::to_string(&triples, &self.prefixes),
+            ),
+            Some(_) => Response::new(
+                200,
+                N_TRIPLES,
+                triples
+                    .iter()
+                    .map(|triple| format!("{}\n", triple))
+                    .collect(),
+            ),
+            None => Response::error(406, format!("graphs are {}, {}", TURTLE, N_TRIPLES)),
+        }
+    }
+}
+
+// each connection gets its own thread so a slow client doesn't hold up the others, requests are
+// read and answered in parallel but handled one at a time, neither a failed accept nor a broken
+// connection stops the server
+pub fn serve(listener: TcpListener, server: &mut Server) -> io::Result<()> {
+    let server = Mutex::new(server);
+
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let server = &server;
+
+                    scope.spawn(move || answer(stream, server));
+                }
+                Err(error) => eprintln!("Failed to accept a connection: {}", error),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn answer(mut stream: TcpStream, server: &Mutex<&mut Server>) {
+    // a client that never finishes its request only keeps its own thread around
+    if let Err(error) = stream.set_read_timeout(Some(Duration::from_secs(30))) {
+        eprintln!("Failed to set up a connection: {}", error);
+        return;
+    }
+
+    let (response, head) = match Request::read(&mut BufReader::new(&stream)) {
+        Ok(request) => {
+            // keep serving after a handler panicked
+            let mut server = server.lock().unwrap_or_else(|error| error.into_inner());
+
+            (server.handle(&request), request.method == "HEAD")
+        }
+        Err(error) if error.kind() == io::ErrorKind::InvalidData => (
+            Response::error(error_status(&error), error.to_string()),
+            false,
+        ),
+        Err(_) => return,
+    };
+
+    let _ = match head {
+        true => response.write_head(&mut stream),
+        false => response.write(&mut stream),
+    };
+}
+
+// the triples of a turtle or n-triples body, relative iris resolve against the graph name
+fn parse_body(request: &Request, name: Option<&NamedNode>) -> Result<Vec<Triple>, Response> {
+    let invalid = |error: ParseError| Response::error(400, format!("invalid graph: {}", error));
+
+    match request.content_type().as_deref() {
+        Some(TURTLE) | None => {
+            let mut context = ParseContext::new();
+            let mut triples: Vec<Triple> = Vec::new();
+
+            context.base = name.map(|name| name.as_str().to_string());
+            parser::parse_reader_into(request.body.as_bytes(), &mut context, &mut triples)
+                .map_err(invalid)?;
+
+            Ok(triples)
+        }
+        Some(N_TRIPLES) => NTriplesParser::from(request.body.as_str())
+            .collect::<Result<Vec<Triple>, ParseError>>()
+            .map_err(invalid),
+        Some(content_type) => Err(Response::error(
+            415,
+            format!("expected {} or {}, got {}", TURTLE, N_TRIPLES, content_type),
+        )),
+    }
+}
+
+fn method_not_allowed(allowed: &str) -> Response {
+    let mut response = Response::error(405, format!("allowed methods are {}", allowed));
+
+    response
+        .headers
+        .push(("Allow".to_string(), allowed.to_string()));
+    response
+}
+
+// the offered media type with the highest quality in the accept header, earlier ones win ties,
+// no header accepts the first one
+pub fn negotiate<'a>(accept: Option<&str>, offered: &[&'a str]) -> Option<&'a str> {
+    let accept = match accept {
+        Some(accept) if !accept.trim().is_empty() => accept,
+        _ => return offered.first().copied(),
+    };
+
+    // the quality of the most specific range that matches, `type/subtype` before `type/*` before `*/*`
+    let quality = |offered: &str| {
+        let (kind, _) = offered.split_once('/').unwrap_or((offered, ""));
+
+        accept
+            .split(',')
+            .filter_map(|range| {
+                let mut parameters = range.split(';');
+                let range = parameters.next()?.trim().to_ascii_lowercase();
+                let specificity = match range.as_str() {
+                    "*/*" => 0,
+                    range if range == format!("{}/*", kind) => 1,
+                    range if range == offered => 2,
+                    _ => return None,
+                };
+                let quality = parameters
+                    .filter_map(|parameter| parameter.trim().strip_prefix("q="))
+                    .find_map(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+
+                Some((specificity, quality))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map_or(0.0, |(_, quality)| quality)
+    };
+
+    let mut best: Option<(&str, f32)> = None;
+
+    for media_type in offered {
+        let quality = quality(media_type);
+
+        if quality > 0.0 && best.is_none_or(|(_, best)| quality > best) {
+            best = Some((media_type, quality));
+        }
+    }
+
+    best.map(|(media_type, _)| media_type)
+}
+
+fn media_type(content_type: &str) -> String {
+    let (media_type, _) = content_type.split_once(';').unwrap_or((content_type, ""));
+
+    media_type.trim().to_ascii_lowercase()
+}
+
+// `application/x-www-form-urlencoded` pairs, also used for query strings
+fn decode_form(form: &str) -> Vec<(String, String)> {
+    form.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            (decode(name, true), decode(value, true))
+        })
+        .collect()
+}
+
+// percent decoding, invalid escapes are kept as they are
+fn decode(text: &str, plus_as_space: bool) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = match bytes[i] {
+            b'%' => text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (None, b'+') if plus_as_space => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (None, byte) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+// a line of the request line and headers, which together may be at most MAX_HEAD bytes
+fn read_head_line(
+    reader: &mut impl BufRead,
+    line: &mut String,
+    head: &mut usize,
+) -> io::Result<usize> {
+    line.clear();
+
+    let length = reader
+        .by_ref()
+        .take((MAX_HEAD - *head) as u64)
+        .read_line(line)?;
+
+    *head += length;
+
+    match *head == MAX_HEAD && !line.ends_with('\n') {
+        true => Err(invalid_request(
+            431,
+            "the request header fields are too large",
+        )),
+        false => Ok(length),
+    }
+}
+
+// a request that is answered with an error status instead of being handled
+#[derive(Debug)]
+struct InvalidRequest {
+    status: u16,
+    message: String,
+}
+
+impl fmt::Display for InvalidRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for InvalidRequest {}
+
+fn invalid_request(status: u16, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        InvalidRequest {
+            status,
+            message: message.to_string(),
+        },
+    )
+}
+
+// the status to answer an InvalidData error of Request::read with
+pub fn error_status(error: &io::Error) -> u16 {
+    error
+        .get_ref()
+        .and_then(|error| error.downcast_ref::<InvalidRequest>())
+        .map_or(400, |error| error.status)
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        413 => "Content Too Large",
+        415 => "Unsupported Media Type",
+        431 => "Request Header Fields Too Large",
+        _ => "",
+    }
+}
@@ -0,0 +1,817 @@
+// a subset of sparql 1.1, SELECT and ASK over an in-memory graph with basic graph patterns,
+// FILTER, OPTIONAL, UNION, ORDER BY, LIMIT, OFFSET and DISTINCT
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::graph::Graph;
+use crate::lexer::{Lexeme, Token};
+use crate::number::{BigDecimal, BigInt};
+use crate::parser::{self, ParseContext, ParseError};
+use crate::query::{self, BasicGraphPattern, Bindings};
+use crate::sparql_regex::Regex;
+use crate::term::*;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum QueryForm {
+    Select {
+        variables: Option<Vec<String>>, // None for `SELECT *`
+        distinct: bool,
+    },
+    Ask,
+}
+
+// the parts of a `{ ... }` group
+#[derive(PartialEq, Debug, Clone)]
+pub enum Element {
+    Triples(BasicGraphPattern),
+    Group(GroupPattern),
+    Optional(GroupPattern),
+    Union(Vec<GroupPattern>),
+    Filter(Expression),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct GroupPattern {
+    pub elements: Vec<Element>,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Function {
+    Regex,
+    Lang,
+    LangMatches,
+    Datatype,
+    Str,
+    Bound,
+    IsIri,
+    IsBlank,
+    IsLiteral,
+    SameTerm,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Expression {
+    Term(Term),
+    Variable(String),
+    Or(Box<Expression>, Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Compare(Comparison, Box<Expression>, Box<Expression>),
+    Call(Function, Vec<Expression>),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct OrderCondition {
+    pub expression: Expression,
+    pub descending: bool,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Query {
+    pub form: QueryForm,
+    pub pattern: GroupPattern,
+    pub order_by: Vec<OrderCondition>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum QueryResults {
+    Solutions {
+        variables: Vec<String>,
+        solutions: Vec<Bindings>, // unbound variables are missing
+    },
+    Boolean(bool),
+}
+
+impl Query {
+    // PREFIX and BASE declarations are added to the context, prefixes declared there can be used
+    pub fn parse(text: &str, context: &mut ParseContext) -> Result<Query, ParseError> {
+        let mut parser = QueryParser {
+            tokens: query::tokenize_query(text)?,
+            position: 0,
+            context,
+        };
+
+        parser.query()
+    }
+
+    pub fn evaluate(&self, graph: &Graph) -> QueryResults {
+        let mut solutions = self.pattern.evaluate(graph, vec![Bindings::new()]);
+
+        let (variables, distinct) = match &self.form {
+            QueryForm::Ask => return QueryResults::Boolean(!solutions.is_empty()),
+            QueryForm::Select {
+                variables,
+                distinct,
+            } => (variables, *distinct),
+        };
+
+        if !self.order_by.is_empty() {
+            solutions.sort_by(|a, b| self.compare(a, b));
+        }
+
+        let variables = variables
+            .clone()
+            .unwrap_or_else(|| self.pattern.variables());
+        let mut seen: HashSet<Bindings> = HashSet::new();
+        let solutions = solutions
+            .into_iter()
+            .map(|bindings| {
+                bindings
+                    .into_iter()
+                    .filter(|(variable, _)| variables.contains(variable))
+                    .collect()
+            })
+            .filter(|bindings: &Bindings| !distinct || seen.insert(bindings.clone()))
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+
+        QueryResults::Solutions {
+            variables,
+            solutions,
+        }
+    }
+
+    fn compare(&self, a: &Bindings, b: &Bindings) -> Ordering {
+        for condition in &self.order_by {
+            let a = condition.expression.evaluate(a);
+            let b = condition.expression.evaluate(b);
+            let ordering = order_terms(a.as_ref(), b.as_ref());
+
+            match (ordering, condition.descending) {
+                (Ordering::Equal, _) => continue,
+                (ordering, true) => return ordering.reverse(),
+                (ordering, false) => return ordering,
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+impl GroupPattern {
+    // the variables of its patterns in order of their first appearance, filters don't bind any
+    pub fn variables(&self) -> Vec<String> {
+        let mut variables: Vec<String> = Vec::new();
+
+        for element in &self.elements {
+            let element_variables = match element {
+                Element::Triples(pattern) => pattern.variables(),
+                Element::Group(group) | Element::Optional(group) => group.variables(),
+                Element::Union(groups) => groups.iter().flat_map(GroupPattern::variables).collect(),
+                Element::Filter(_) => Vec::new(),
+            };
+
+            for variable in element_variables {
+                if !variables.contains(&variable) {
+                    variables.push(variable);
+                }
+            }
+        }
+
+        variables
+    }
+
+    // extends each of the input solutions, groups are evaluated with the solutions so far, which
+    // only differs from sparql for filters in nested groups that use variables bound outside of them
+    pub fn evaluate(&self, graph: &Graph, input: Vec<Bindings>) -> Vec<Bindings> {
+        let mut solutions = input;
+        let mut filters: Vec<&Expression> = Vec::new();
+
+        for element in &self.elements {
+            solutions = match element {
+                Element::Triples(pattern) => pattern.evaluate_with(graph, solutions),
+                Element::Group(group) => group.evaluate(graph, solutions),
+                Element::Optional(group) => solutions
+                    .into_iter()
+                    .flat_map(
+                        |bindings| match group.evaluate(graph, vec![bindings.clone()]) {
+                            extended if extended.is_empty() => vec![bindings],
+                            extended => extended,
+                        },
+                    )
+                    .collect(),
+                Element::Union(groups) => solutions
+                    .iter()
+                    .flat_map(|bindings| {
+                        groups
+                            .iter()
+                            .flat_map(|group| group.evaluate(graph, vec![bindings.clone()]))
+                    })
+                    .collect(),
+                Element::Filter(expression) => {
+                    filters.push(expression);
+                    solutions
+                }
+            };
+        }
+
+        // filters apply to the whole group, wherever they are written
+        solutions.retain(|bindings| filters.iter().all(|filter| filter.is_true(bindings)));
+        solutions
+    }
+}
+
+impl Expression {
+    // None is an error, like an unbound variable or comparing an iri to a number
+    pub fn evaluate(&self, bindings: &Bindings) -> Option<Term> {
+        match self {
+            Expression::Term(term) => Some(term.clone()),
+            Expression::Variable(variable) => bindings.get(variable).cloned(),
+            // an error on one side doesn't matter if the other side decides
+            Expression::Or(a, b) => match (a.boolean(bindings), b.boolean(bindings)) {
+                (Some(true), _) | (_, Some(true)) => Some(boolean(true)),
+                (Some(false), Some(false)) => Some(boolean(false)),
+                _ => None,
+            },
+            Expression::And(a, b) => match (a.boolean(bindings), b.boolean(bindings)) {
+                (Some(false), _) | (_, Some(false)) => Some(boolean(false)),
+                (Some(true), Some(true)) => Some(boolean(true)),
+                _ => None,
+            },
+            Expression::Not(expression) => {
+                expression.boolean(bindings).map(|value| boolean(!value))
+            }
+            Expression::Compare(comparison, a, b) => {
+                let a = a.evaluate(bindings)?;
+                let b = b.evaluate(bindings)?;
+
+                compare(*comparison, &a, &b).map(boolean)
+            }
+            Expression::Call(function, arguments) => call(*function, arguments, bindings),
+        }
+    }
+
+    // errors filter a solution out
+    pub fn is_true(&self, bindings: &Bindings) -> bool {
+        self.boolean(bindings).unwrap_or(false)
+    }
+
+    fn boolean(&self, bindings: &Bindings) -> Option<bool> {
+        effective_boolean(&self.evaluate(bindings)?)
+    }
+}
+
+fn boolean(value: bool) -> Term {
+    Term::from(Literal::new_typed(value.to_string(), XSD_BOOLEAN))
+}
+
+fn simple(value: impl Into<String>) -> Term {
+    Term::from(Literal::new_simple(value))
+}
+
+// the effective boolean value, section 17.2.2
+fn effective_boolean(term: &Term) -> Option<bool> {
+    let literal = match term {
+        Term::Literal(literal) => literal,
+        _ => return None,
+    };
+
+    match literal.datatype.as_str() {
+        XSD_BOOLEAN => literal.as_bool(),
+        XSD_STRING | RDF_LANG_STRING => Some(!literal.value.is_empty()),
+        _ => match number(literal)? {
+            Number::Exact(decimal) => Some(decimal.cmp(&BigDecimal::from(BigInt::from(0))).is_ne()),
+            Number::Double(double) => Some(double != 0.0 && !double.is_nan()),
+        },
+    }
+}
+
+enum Number {
+    Exact(BigDecimal), // xsd:integer and xsd:decimal
+    Double(f64),
+}
+
+fn number(literal: &Literal) -> Option<Number> {
+    match literal.as_decimal() {
+        Some(decimal) => Some(Number::Exact(decimal)),
+        None => literal.as_double().map(Number::Double),
+    }
+}
+
+// the order of two literals by their values, None if they can't be compared
+fn value_order(a: &Term, b: &Term) -> Option<Ordering> {
+    let (a, b) = match (a, b) {
+        (Term::Literal(a), Term::Literal(b)) => (a, b),
+        _ => return None,
+    };
+
+    match (number(a), number(b)) {
+        (Some(Number::Exact(a)), Some(Number::Exact(b))) => return Some(a.cmp(&b)),
+        (Some(Number::Exact(a)), Some(Number::Double(b))) => return a.to_f64().partial_cmp(&b),
+        (Some(Number::Double(a)), Some(Number::Exact(b))) => return a.partial_cmp(&b.to_f64()),
+        (Some(Number::Double(a)), Some(Number::Double(b))) => return a.partial_cmp(&b),
+        _ => {}
+    }
+
+    match (a.datatype.as_str(), b.datatype.as_str()) {
+        (XSD_STRING, XSD_STRING) => Some(a.value.cmp(&b.value)),
+        (XSD_BOOLEAN, XSD_BOOLEAN) => a.as_bool()?.partial_cmp(&b.as_bool()?),
+        _ => None,
+    }
+}
+
+fn compare(comparison: Comparison, a: &Term, b: &Term) -> Option<bool> {
+    let ordering = value_order(a, b);
+
+    match comparison {
+        // terms that can't be compared by value are equal if they are the same term
+        Comparison::Equal => Some(ordering.map_or(a == b, Ordering::is_eq)),
+        Comparison::NotEqual => Some(ordering.map_or(a != b, Ordering::is_ne)),
+        Comparison::Less => ordering.map(Ordering::is_lt),
+        Comparison::LessOrEqual => ordering.map(Ordering::is_le),
+        Comparison::Greater => ordering.map(Ordering::is_gt),
+        Comparison::GreaterOrEqual => ordering.map(Ordering::is_ge),
+    }
+}
+
+// ORDER BY puts unbound first, then blank nodes, iris and literals, section 15.1
+fn order_terms(a: Option<&Term>, b: Option<&Term>) -> Ordering {
+    let rank = |term: Option<&Term>| match term {
+        None => 0,
+        Some(Term::BlankNode(_)) => 1,
+        Some(Term::NamedNode(_)) => 2,
+        Some(Term::Literal(_)) => 3,
+    };
+
+    match (a, b) {
+        (Some(a), Some(b)) if rank(Some(a)) == rank(Some(b)) => {
+            value_order(a, b).unwrap_or_else(|| a.cmp(b))
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+// the lexical form of a string literal
+fn string(term: &Term) -> Option<&str> {
+    match term {
+        Term::Literal(literal) if literal.is_plain() || literal.language.is_some() => {
+            Some(&literal.value)
+        }
+        _ => None,
+    }
+}
+
+fn call(function: Function, arguments: &[Expression], bindings: &Bindings) -> Option<Term> {
+    // bound is the only function that takes an unbound variable
+    if let (Function::Bound, [Expression::Variable(variable)]) = (function, arguments) {
+        return Some(boolean(bindings.contains_key(variable)));
+    }
+
+    let arguments = arguments
+        .iter()
+        .map(|argument| argument.evaluate(bindings))
+        .collect::<Option<Vec<Term>>>()?;
+
+    match (function, arguments.as_slice()) {
+        (Function::Regex, [text, pattern, flags @ ..]) => {
+            let flags = match flags {
+                [flags] => string(flags)?,
+                _ => "",
+            };
+            let regex = Regex::new(string(pattern)?, flags).ok()?;
+
+            Some(boolean(regex.is_match(string(text)?)))
+        }
+        (Function::Lang, [Term::Literal(literal)]) => {
+            Some(simple(literal.language.clone().unwrap_or_default()))
+        }
+        (Function::LangMatches, [tag, range]) => {
+            let (tag, range) = (
+                string(tag)?.to_ascii_lowercase(),
+                string(range)?.to_ascii_lowercase(),
+            );
+            let matches = match range.as_str() {
+                "*" => !tag.is_empty(),
+                range => tag == range || tag.starts_with(&format!("{}-", range)),
+            };
+
+            Some(boolean(matches))
+        }
+        (Function::Datatype, [Term::Literal(literal)]) => {
+            Some(Term::from(literal.datatype.clone()))
+        }
+        (Function::Str, [Term::NamedNode(named_node)]) => Some(simple(named_node.as_str())),
+        (Function::Str, [Term::Literal(literal)]) => Some(simple(literal.value.clone())),
+        (Function::IsIri, [term]) => Some(boolean(matches!(term, Term::NamedNode(_)))),
+        (Function::IsBlank, [term]) => Some(boolean(matches!(term, Term::BlankNode(_)))),
+        (Function::IsLiteral, [term]) => Some(boolean(matches!(term, Term::Literal(_)))),
+        (Function::SameTerm, [a, b]) => Some(boolean(a == b)),
+        _ => None,
+    }
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Function> {
+        let function = match name.to_ascii_lowercase().as_str() {
+            "regex" => Function::Regex,
+            "lang" => Function::Lang,
+            "langmatches" => Function::LangMatches,
+            "datatype" => Function::Datatype,
+            "str" => Function::Str,
+            "bound" => Function::Bound,
+            "isiri" | "isuri" => Function::IsIri,
+            "isblank" => Function::IsBlank,
+            "isliteral" => Function::IsLiteral,
+            "sameterm" => Function::SameTerm,
+            _ => return None,
+        };
+
+        Some(function)
+    }
+
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Function::Regex => (2, 3),
+            Function::LangMatches | Function::SameTerm => (2, 2),
+            _ => (1, 1),
+        }
+    }
+}
+
+struct QueryParser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    context: &'a mut ParseContext,
+}
+
+impl QueryParser<'_> {
+    fn query(&mut self) -> Result<Query, ParseError> {
+        // the prologue
+        while let Some(
+            token @ Token {
+                lexeme: Lexeme::Prefix(_, _) | Lexeme::Base(_),
+                ..
+            },
+        ) = self.tokens.get(self.position)
+        {
+            parser::parse(std::slice::from_ref(token), self.context)?;
+            self.position += 1;
+        }
+
+        let form = match self.next()? {
+            token if is_keyword(token, "SELECT") => self.select()?,
+            token if is_keyword(token, "ASK") => QueryForm::Ask,
+            token => return Err(parser::unexpected_token(token)),
+        };
+
+        self.eat_keyword("WHERE");
+
+        let mut query = Query {
+            form,
+            pattern: self.group()?,
+            order_by: Vec::new(),
+            limit: None,
+            offset: 0,
+        };
+
+        if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            query.order_by = self.order_conditions()?;
+        }
+
+        loop {
+            if self.eat_keyword("LIMIT") {
+                query.limit = Some(self.count()?);
+            } else if self.eat_keyword("OFFSET") {
+                query.offset = self.count()?;
+            } else {
+                break;
+            }
+        }
+
+        match self.peek() {
+            Some(token) => Err(parser::unexpected_token(token)),
+            None => Ok(query),
+        }
+    }
+
+    fn select(&mut self) -> Result<QueryForm, ParseError> {
+        let distinct = self.eat_keyword("DISTINCT");
+
+        if self.eat(&Lexeme::Operator("*".to_string())) {
+            return Ok(QueryForm::Select {
+                variables: None,
+                distinct,
+            });
+        }
+
+        let mut variables = Vec::new();
+
+        while let Some(Token {
+            lexeme: Lexeme::Variable(variable),
+            ..
+        }) = self.peek()
+        {
+            variables.push(variable.clone());
+            self.position += 1;
+        }
+
+        match variables.is_empty() {
+            true => Err(self.unexpected()),
+            false => Ok(QueryForm::Select {
+                variables: Some(variables),
+                distinct,
+            }),
+        }
+    }
+
+    // `{ ... }`, the triples between the other elements are parsed as basic graph patterns
+    fn group(&mut self) -> Result<GroupPattern, ParseError> {
+        self.expect(&Lexeme::GraphStartToken)?;
+
+        let mut group = GroupPattern::default();
+        let mut triples: Vec<Token> = Vec::new();
+
+        loop {
+            let token = self.peek().ok_or_else(|| self.unexpected())?.clone();
+
+            if token.lexeme == Lexeme::GraphEndToken {
+                self.flush(&mut triples, &mut group)?;
+                self.position += 1;
+                return Ok(group);
+            }
+
+            if token.lexeme == Lexeme::GraphStartToken {
+                self.flush(&mut triples, &mut group)?;
+
+                let mut groups = vec![self.group()?];
+
+                while self.eat_keyword("UNION") {
+                    groups.push(self.group()?);
+                }
+
+                group.elements.push(match groups.len() {
+                    1 => Element::Group(groups.remove(0)),
+                    _ => Element::Union(groups),
+                });
+            } else if self.eat_keyword("OPTIONAL") {
+                self.flush(&mut triples, &mut group)?;
+                group.elements.push(Element::Optional(self.group()?));
+            } else if self.eat_keyword("FILTER") {
+                self.flush(&mut triples, &mut group)?;
+                group.elements.push(Element::Filter(self.constraint()?));
+            } else {
+                triples.push(token);
+                self.position += 1;
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        triples: &mut Vec<Token>,
+        group: &mut GroupPattern,
+    ) -> Result<(), ParseError> {
+        if !triples.is_empty() {
+            let pattern = BasicGraphPattern::from_tokens(triples, self.context)?;
+
+            group.elements.push(Element::Triples(pattern));
+            triples.clear();
+        }
+
+        Ok(())
+    }
+
+    // `( expression )` or a function call
+    fn constraint(&mut self) -> Result<Expression, ParseError> {
+        match self.peek() {
+            Some(Token {
+                lexeme: Lexeme::CollectionStartToken,
+                ..
+            }) => self.bracketed(),
+            Some(Token {
+                lexeme: Lexeme::Unknown(name),
+                ..
+            }) if Function::from_name(name).is_some() => self.primary(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn order_conditions(&mut self) -> Result<Vec<OrderCondition>, ParseError> {
+        let mut conditions = Vec::new();
+
+        loop {
+            let descending = self.eat_keyword("DESC");
+            let expression = match descending || self.eat_keyword("ASC") {
+                true => self.bracketed()?,
+                false => match self.peek().map(|token| &token.lexeme) {
+                    Some(Lexeme::Variable(_) | Lexeme::CollectionStartToken) => self.primary()?,
+                    Some(Lexeme::Unknown(name)) if Function::from_name(name).is_some() => {
+                        self.primary()?
+                    }
+                    _ => break,
+                },
+            };
+
+            conditions.push(OrderCondition {
+                expression,
+                descending,
+            });
+        }
+
+        match conditions.is_empty() {
+            true => Err(self.unexpected()),
+            false => Ok(conditions),
+        }
+    }
+
+    fn bracketed(&mut self) -> Result<Expression, ParseError> {
+        self.expect(&Lexeme::CollectionStartToken)?;
+
+        let expression = self.expression()?;
+
+        self.expect(&Lexeme::CollectionEndToken)?;
+
+        Ok(expression)
+    }
+
+    fn expression(&mut self) -> Result<Expression, ParseError> {
+        let mut expression = self.conjunction()?;
+
+        while self.eat(&Lexeme::Operator("||".to_string())) {
+            expression = Expression::Or(Box::new(expression), Box::new(self.conjunction()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn conjunction(&mut self) -> Result<Expression, ParseError> {
+        let mut expression = self.relational()?;
+
+        while self.eat(&Lexeme::Operator("&&".to_string())) {
+            expression = Expression::And(Box::new(expression), Box::new(self.relational()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn relational(&mut self) -> Result<Expression, ParseError> {
+        let expression = self.unary()?;
+        let comparison = match self.peek().map(|token| &token.lexeme) {
+            Some(Lexeme::Operator(operator)) => match operator.as_str() {
+                "=" => Comparison::Equal,
+                "!=" => Comparison::NotEqual,
+                "<" => Comparison::Less,
+                "<=" => Comparison::LessOrEqual,
+                ">" => Comparison::Greater,
+                ">=" => Comparison::GreaterOrEqual,
+                _ => return Ok(expression),
+            },
+            _ => return Ok(expression),
+        };
+
+        self.position += 1;
+
+        Ok(Expression::Compare(
+            comparison,
+            Box::new(expression),
+            Box::new(self.unary()?),
+        ))
+    }
+
+    fn unary(&mut self) -> Result<Expression, ParseError> {
+        match self.eat(&Lexeme::Operator("!".to_string())) {
+            true => Ok(Expression::Not(Box::new(self.unary()?))),
+            false => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression, ParseError> {
+        let token = self.next()?.clone();
+
+        match &token.lexeme {
+            Lexeme::CollectionStartToken => {
+                self.position -= 1;
+                self.bracketed()
+            }
+            Lexeme::Variable(variable) => Ok(Expression::Variable(variable.clone())),
+            Lexeme::Iri(_) | Lexeme::PrefixedIri(_) => Ok(Expression::Term(Term::from(
+                parser::parse_iri(&token, self.context)?,
+            ))),
+            Lexeme::Unknown(name) => match Function::from_name(name) {
+                Some(function) => self.call(function, &token),
+                None => Ok(Expression::Term(Term::from(parser::parse_literal(
+                    &token,
+                    self.context,
+                )?))),
+            },
+            _ => Ok(Expression::Term(Term::from(parser::parse_literal(
+                &token,
+                self.context,
+            )?))),
+        }
+    }
+
+    fn call(&mut self, function: Function, name: &Token) -> Result<Expression, ParseError> {
+        self.expect(&Lexeme::CollectionStartToken)?;
+
+        let mut arguments = Vec::new();
+
+        if !self.eat(&Lexeme::CollectionEndToken) {
+            arguments.push(self.expression()?);
+
+            while self.eat(&Lexeme::ObjectListToken) {
+                arguments.push(self.expression()?);
+            }
+
+            self.expect(&Lexeme::CollectionEndToken)?;
+        }
+
+        let (min, max) = function.arity();
+        let bound_variable = function != Function::Bound
+            || matches!(arguments.as_slice(), [Expression::Variable(_)]);
+
+        match (min..=max).contains(&arguments.len()) && bound_variable {
+            true => Ok(Expression::Call(function, arguments)),
+            false => Err(ParseError::InvalidSyntax {
+                message: format!("invalid arguments for `{}`", name.lexeme),
+                span: name.span,
+            }),
+        }
+    }
+
+    // LIMIT and OFFSET
+    fn count(&mut self) -> Result<usize, ParseError> {
+        let token = self.next()?;
+
+        match &token.lexeme {
+            Lexeme::Unknown(count) => count.parse().map_err(|_| parser::unexpected_token(token)),
+            _ => Err(parser::unexpected_token(token)),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<&Token, ParseError> {
+        self.position += 1;
+
+        match self.tokens.get(self.position - 1) {
+            Some(token) => Ok(token),
+            None => Err(self.unexpected()),
+        }
+    }
+
+    fn eat(&mut self, lexeme: &Lexeme) -> bool {
+        let found = self.peek().is_some_and(|token| token.lexeme == *lexeme);
+
+        if found {
+            self.position += 1;
+        }
+
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek().is_some_and(|token| is_keyword(token, keyword));
+
+        if found {
+            self.position += 1;
+        }
+
+        found
+    }
+
+    fn expect(&mut self, lexeme: &Lexeme) -> Result<(), ParseError> {
+        match self.eat(lexeme) {
+            true => Ok(()),
+            false => Err(self.unexpected()),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        match self.eat_keyword(keyword) {
+            true => Ok(()),
+            false => Err(self.unexpected()),
+        }
+    }
+
+    // the current token, or the end of the query
+    fn unexpected(&self) -> ParseError {
+        match (self.tokens.get(self.position), self.tokens.last()) {
+            (Some(token), _) => parser::unexpected_token(token),
+            (None, last) => ParseError::UnexpectedEndOfInput {
+                span: last.map(|token| token.span).unwrap_or_default(),
+            },
+        }
+    }
+}
+
+// keywords are lexed as unknown words and are case insensitive
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(&token.lexeme, Lexeme::Unknown(word) if word.eq_ignore_ascii_case(keyword))
+}
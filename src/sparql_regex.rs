@@ -0,0 +1,38 @@
+// the regular expressions of sparql's regex function with the `i`, `s`, `m`, `x` and `q` flags,
+// matched by the regex crate in linear time so that no pattern can hang or crash a query
+use regex::RegexBuilder;
+
+#[derive(Debug, Clone)]
+pub struct Regex(regex::Regex);
+
+impl Regex {
+    pub fn new(pattern: &str, flags: &str) -> Result<Regex, String> {
+        let literal = flags.contains('q');
+        let pattern = match literal {
+            true => regex::escape(pattern),
+            false => pattern.to_string(),
+        };
+        let mut builder = RegexBuilder::new(&pattern);
+
+        for flag in flags.chars() {
+            match flag {
+                'i' => builder.case_insensitive(true),
+                's' => builder.dot_matches_new_line(true),
+                'm' => builder.multi_line(true),
+                'x' => builder.ignore_whitespace(true),
+                'q' => continue,
+                flag => return Err(format!("unknown regex flag `{}`", flag)),
+            };
+        }
+
+        builder
+            .build()
+            .map(Regex)
+            .map_err(|error| error.to_string())
+    }
+
+    // true if the pattern matches anywhere in the text
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
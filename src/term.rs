@@ -0,0 +1,334 @@
+// rdf terms and triples, see RDF 1.1 Concepts section 3
+use std::fmt;
+
+use crate::ntriples;
+use crate::number::{BigDecimal, BigInt};
+
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+pub const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+pub const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+pub const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+pub const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+pub const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+pub const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+pub const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+pub const XSD_DECIMAL: &str = "http://www.w3.org/2001/XMLSchema#decimal";
+pub const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+
+// an absolute iri
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub struct NamedNode(pub String);
+
+impl NamedNode {
+    pub fn new(iri: impl Into<String>) -> NamedNode {
+        NamedNode(iri.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+// document scoped blank node identifier
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub struct BlankNode(pub String);
+
+impl BlankNode {
+    pub fn new(id: impl Into<String>) -> BlankNode {
+        BlankNode(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+// how a literal is written doesn't matter, `"""a"""`, `'a'` and `"a"^^xsd:string` are the same literal
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub struct Literal {
+    pub value: String,
+    pub datatype: NamedNode, // rdf:langString for language tagged strings
+    pub language: Option<String>, // lowercase, tags are case insensitive
+}
+
+impl Literal {
+    pub fn new_simple(value: impl Into<String>) -> Literal {
+        Literal::new_typed(value, XSD_STRING)
+    }
+
+    pub fn new_typed(value: impl Into<String>, datatype: impl Into<String>) -> Literal {
+        Literal {
+            value: value.into(),
+            datatype: NamedNode(datatype.into()),
+            language: None,
+        }
+    }
+
+    pub fn new_language_tagged(value: impl Into<String>, language: impl Into<String>) -> Literal {
+        Literal {
+            value: value.into(),
+            datatype: NamedNode(RDF_LANG_STRING.to_string()),
+            language: Some(language.into().to_ascii_lowercase()),
+        }
+    }
+
+    pub fn is_plain(&self) -> bool {
+        self.datatype.0 == XSD_STRING
+    }
+
+    // the native values, None for other datatypes or invalid lexical forms
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match (self.datatype.0.as_str(), self.value.as_str()) {
+            (XSD_BOOLEAN, "true" | "1") => Some(true),
+            (XSD_BOOLEAN, "false" | "0") => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<BigInt> {
+        match self.datatype.0.as_str() {
+            XSD_INTEGER => BigInt::parse(&self.value),
+            _ => None,
+        }
+    }
+
+    // integers are decimals as well
+    pub fn as_decimal(&self) -> Option<BigDecimal> {
+        match self.datatype.0.as_str() {
+            XSD_DECIMAL => BigDecimal::parse(&self.value),
+            XSD_INTEGER => self.as_integer().map(BigDecimal::from),
+            _ => None,
+        }
+    }
+
+    pub fn as_double(&self) -> Option<f64> {
+        if self.datatype.0 != XSD_DOUBLE {
+            return None;
+        }
+
+        match self.value.as_str() {
+            "INF" | "+INF" => Some(f64::INFINITY),
+            "-INF" => Some(f64::NEG_INFINITY),
+            "NaN" => Some(f64::NAN),
+            // rules out `inf` and `nan` which rust happily parses as floats
+            value
+                if value
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || "+-.eE".contains(c)) =>
+            {
+                value.parse().ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub enum Term {
+    NamedNode(NamedNode),
+    BlankNode(BlankNode),
+    Literal(Literal),
+}
+
+impl From<NamedNode> for Term {
+    fn from(named_node: NamedNode) -> Self {
+        Term::NamedNode(named_node)
+    }
+}
+
+impl From<BlankNode> for Term {
+    fn from(blank_node: BlankNode) -> Self {
+        Term::BlankNode(blank_node)
+    }
+}
+
+impl From<Literal> for Term {
+    fn from(literal: Literal) -> Self {
+        Term::Literal(literal)
+    }
+}
+
+impl From<Subject> for Term {
+    fn from(subject: Subject) -> Self {
+        match subject {
+            Subject::NamedNode(named_node) => Term::NamedNode(named_node),
+            Subject::BlankNode(blank_node) => Term::BlankNode(blank_node),
+        }
+    }
+}
+
+// literals can't be subjects
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub enum Subject {
+    NamedNode(NamedNode),
+    BlankNode(BlankNode),
+}
+
+impl From<NamedNode> for Subject {
+    fn from(named_node: NamedNode) -> Self {
+        Subject::NamedNode(named_node)
+    }
+}
+
+impl From<BlankNode> for Subject {
+    fn from(blank_node: BlankNode) -> Self {
+        Subject::BlankNode(blank_node)
+    }
+}
+
+impl TryFrom<Term> for Subject {
+    type Error = Term;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        match term {
+            Term::NamedNode(named_node) => Ok(Subject::NamedNode(named_node)),
+            Term::BlankNode(blank_node) => Ok(Subject::BlankNode(blank_node)),
+            term => Err(term),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub struct Triple {
+    pub subject: Subject,
+    pub predicate: NamedNode,
+    pub object: Term,
+}
+
+// the name of a trig `{ ... }` block
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub enum GraphName {
+    NamedNode(NamedNode),
+    BlankNode(BlankNode),
+}
+
+impl From<NamedNode> for GraphName {
+    fn from(named_node: NamedNode) -> Self {
+        GraphName::NamedNode(named_node)
+    }
+}
+
+impl From<BlankNode> for GraphName {
+    fn from(blank_node: BlankNode) -> Self {
+        GraphName::BlankNode(blank_node)
+    }
+}
+
+impl From<Subject> for GraphName {
+    fn from(subject: Subject) -> Self {
+        match subject {
+            Subject::NamedNode(named_node) => GraphName::NamedNode(named_node),
+            Subject::BlankNode(blank_node) => GraphName::BlankNode(blank_node),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub struct Quad {
+    pub subject: Subject,
+    pub predicate: NamedNode,
+    pub object: Term,
+    pub graph_name: Option<GraphName>, // None for the default graph
+}
+
+impl Quad {
+    pub fn new(triple: Triple, graph_name: Option<GraphName>) -> Quad {
+        Quad {
+            subject: triple.subject,
+            predicate: triple.predicate,
+            object: triple.object,
+            graph_name,
+        }
+    }
+}
+
+impl From<Triple> for Quad {
+    fn from(triple: Triple) -> Self {
+        Quad::new(triple, None)
+    }
+}
+
+// drops the graph name
+impl From<Quad> for Triple {
+    fn from(quad: Quad) -> Self {
+        Triple {
+            subject: quad.subject,
+            predicate: quad.predicate,
+            object: quad.object,
+        }
+    }
+}
+
+// terms and triples display as n-triples, quads as n-quads
+
+impl fmt::Display for NamedNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", ntriples::iri_to_string(&self.0))
+    }
+}
+
+impl fmt::Display for BlankNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "_:{}", self.0)
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = ntriples::literal_to_string(&self.value);
+
+        match &self.language {
+            Some(language) => write!(f, "{}@{}", value, language),
+            // xsd:string is the default datatype and left out in canonical form
+            None if self.is_plain() => write!(f, "{}", value),
+            None => write!(f, "{}^^{}", value, self.datatype),
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Term::NamedNode(named_node) => named_node.fmt(f),
+            Term::BlankNode(blank_node) => blank_node.fmt(f),
+            Term::Literal(literal) => literal.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Subject::NamedNode(named_node) => named_node.fmt(f),
+            Subject::BlankNode(blank_node) => blank_node.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Triple {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} .", self.subject, self.predicate, self.object)
+    }
+}
+
+impl fmt::Display for GraphName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphName::NamedNode(named_node) => named_node.fmt(f),
+            GraphName::BlankNode(blank_node) => blank_node.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Quad {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.subject, self.predicate, self.object)?;
+
+        match &self.graph_name {
+            Some(graph_name) => write!(f, " {} .", graph_name),
+            None => write!(f, " ."),
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+use crate::lexer::{is_pn_chars, is_pn_chars_u};
+use crate::ntriples;
+use crate::number;
+use crate::term::*;
+
+const INDENT: &str = "    ";
+
+// all objects of one predicate of a subject
+struct Predicate<'a> {
+    iri: &'a str,
+    objects: Vec<&'a Term>,
+}
+
+// all triples of one subject
+struct Description<'a> {
+    subject: &'a Subject,
+    predicates: Vec<Predicate<'a>>,
+}
+
+struct Writer<'a> {
+    prefixes: Vec<(&'a str, &'a str)>,
+    descriptions: Vec<Description<'a>>,
+    blank_nodes: HashMap<&'a str, usize>, // description index of a blank node subject
+    references: HashMap<&'a str, usize>,  // how often a blank node is used as object
+    written: HashSet<&'a str>,
+    out: String,
+}
+
+// writes the triples as turtle, grouped by subject and compacted with the given prefixes
+pub fn write_triples<W: Write>(
+    out: &mut W,
+    triples: &[Triple],
+    prefixes: &HashMap<String, String>,
+) -> io::Result<()> {
+    out.write_all(to_string(triples, prefixes).as_bytes())
+}
+
+pub fn to_string(triples: &[Triple], prefixes: &HashMap<String, String>) -> String {
+    let mut prefixes: Vec<(&str, &str)> = prefixes
+        .iter()
+        .map(|(name, namespace)| (name.as_str(), namespace.as_str()))
+        .collect();
+
+    prefixes.sort();
+
+    let mut writer = Writer {
+        prefixes,
+        descriptions: Vec::new(),
+        blank_nodes: HashMap::new(),
+        references: HashMap::new(),
+        written: HashSet::new(),
+        out: String::new(),
+    };
+
+    writer.group(triples);
+    writer.write();
+    writer.out
+}
+
+impl<'a> Writer<'a> {
+    // groups the triples by subject and predicate, keeping the order they appeared in
+    fn group(&mut self, triples: &'a [Triple]) {
+        let mut subjects: HashMap<&Subject, usize> = HashMap::new();
+
+        for triple in triples {
+            let index = *subjects.entry(&triple.subject).or_insert_with(|| {
+                self.descriptions.push(Description {
+                    subject: &triple.subject,
+                    predicates: Vec::new(),
+                });
+                self.descriptions.len() - 1
+            });
+
+            if let Subject::BlankNode(BlankNode(label)) = &triple.subject {
+                self.blank_nodes.insert(label, index);
+            }
+
+            let predicates = &mut self.descriptions[index].predicates;
+            let iri = triple.predicate.0.as_str();
+
+            let predicate = match predicates.iter().position(|predicate| predicate.iri == iri) {
+                Some(position) => &mut predicates[position],
+                None => {
+                    // rdf:type is written first as `a`
+                    let position = match iri == RDF_TYPE {
+                        true => 0,
+                        false => predicates.len(),
+                    };
+
+                    predicates.insert(
+                        position,
+                        Predicate {
+                            iri,
+                            objects: Vec::new(),
+                        },
+                    );
+                    &mut predicates[position]
+                }
+            };
+
+            if predicate.objects.contains(&&triple.object) {
+                continue;
+            }
+
+            predicate.objects.push(&triple.object);
+
+            if let Term::BlankNode(BlankNode(label)) = &triple.object {
+                *self.references.entry(label).or_insert(0) += 1;
+            }
+        }
+    }
+
+    fn write(&mut self) {
+        for (name, namespace) in &self.prefixes {
+            self.out.push_str(&format!(
+                "@prefix {} {} .\n",
+                name,
+                ntriples::iri_to_string(namespace)
+            ));
+        }
+
+        // blank nodes that are used once are written inline where they are used
+        for index in 0..self.descriptions.len() {
+            if !self.is_inlined(self.descriptions[index].subject) {
+                self.write_statement(index);
+            }
+        }
+
+        // blank nodes that only reference each other have no place to be inlined at
+        for index in 0..self.descriptions.len() {
+            if let Subject::BlankNode(BlankNode(label)) = self.descriptions[index].subject {
+                if !self.written.contains(label.as_str()) {
+                    self.write_statement(index);
+                }
+            }
+        }
+    }
+
+    fn is_inlined(&self, subject: &Subject) -> bool {
+        match subject {
+            Subject::BlankNode(BlankNode(label)) => self.references.get(label.as_str()) == Some(&1),
+            Subject::NamedNode(_) => false,
+        }
+    }
+
+    fn write_statement(&mut self, index: usize) {
+        let subject = match self.descriptions[index].subject {
+            Subject::NamedNode(NamedNode(iri)) => self.compact(iri),
+            Subject::BlankNode(BlankNode(label)) => {
+                self.written.insert(label);
+                format!("_:{}", label)
+            }
+        };
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+
+        self.out.push_str(&subject);
+        self.out.push(' ');
+        self.write_predicates(index, 1, false);
+        self.out.push_str(" .\n");
+    }
+
+    fn write_predicates(&mut self, index: usize, depth: usize, new_line: bool) {
+        let description = &self.descriptions[index];
+        let predicates: Vec<(&'a str, Vec<&'a Term>)> = description
+            .predicates
+            .iter()
+            .map(|predicate| (predicate.iri, predicate.objects.clone()))
+            .collect();
+
+        for (position, (iri, objects)) in predicates.into_iter().enumerate() {
+            if position > 0 {
+                self.out.push_str(" ;");
+            }
+
+            if position > 0 || new_line {
+                self.out.push('\n');
+                self.out.push_str(&INDENT.repeat(depth));
+            }
+
+            let predicate = match iri == RDF_TYPE {
+                true => "a".to_string(),
+                false => self.compact(iri),
+            };
+
+            self.out.push_str(&predicate);
+
+            for (position, object) in objects.into_iter().enumerate() {
+                if position > 0 {
+                    self.out.push(',');
+                }
+
+                self.out.push(' ');
+                self.write_object(object, depth);
+            }
+        }
+    }
+
+    fn write_object(&mut self, object: &'a Term, depth: usize) {
+        match object {
+            Term::BlankNode(BlankNode(label))
+                if self.references.get(label.as_str()) == Some(&1)
+                    && !self.written.contains(label.as_str()) =>
+            {
+                self.write_blank_node(label, depth)
+            }
+            Term::BlankNode(blank_node) => self.out.push_str(&blank_node.to_string()),
+            Term::NamedNode(NamedNode(iri)) if iri == RDF_NIL => self.out.push_str("()"),
+            Term::NamedNode(NamedNode(iri)) => {
+                let iri = self.compact(iri);
+                self.out.push_str(&iri);
+            }
+            Term::Literal(literal) => self.write_literal(literal),
+        }
+    }
+
+    fn write_literal(&mut self, literal: &Literal) {
+        let Literal {
+            value,
+            datatype: NamedNode(datatype),
+            language,
+        } = literal;
+
+        // numbers and booleans that match the turtle grammar are written bare
+        let bare = match datatype.as_str() {
+            XSD_BOOLEAN => value == "true" || value == "false",
+            XSD_INTEGER => number::is_integer(value),
+            XSD_DECIMAL => number::is_decimal(value),
+            XSD_DOUBLE => number::is_double(value),
+            _ => false,
+        };
+
+        if bare {
+            self.out.push_str(value);
+        } else if language.is_none()
+            && literal.is_plain()
+            && value.contains('\n')
+            && !value.contains("\"\"\"")
+            && !value.contains(['\\', '\r'])
+            && !value.ends_with('"')
+        {
+            self.out.push_str(&format!("\"\"\"{}\"\"\"", value));
+        } else if language.is_none() && !literal.is_plain() {
+            let datatype = self.compact(datatype);
+
+            self.out.push_str(&ntriples::literal_to_string(value));
+            self.out.push_str("^^");
+            self.out.push_str(&datatype);
+        } else {
+            self.out.push_str(&literal.to_string());
+        }
+    }
+
+    // writes a blank node that is only used here as `( ... )` or `[ ... ]`
+    fn write_blank_node(&mut self, label: &'a str, depth: usize) {
+        if let Some((labels, items)) = self.collection(label) {
+            self.written.extend(labels);
+            self.out.push('(');
+
+            for item in items {
+                self.out.push(' ');
+                self.write_object(item, depth);
+            }
+
+            self.out.push_str(" )");
+            return;
+        }
+
+        self.written.insert(label);
+
+        match self.blank_nodes.get(label) {
+            Some(&index) => {
+                self.out.push('[');
+                self.write_predicates(index, depth + 1, true);
+                self.out.push('\n');
+                self.out.push_str(&INDENT.repeat(depth));
+                self.out.push(']');
+            }
+            None => self.out.push_str("[]"),
+        }
+    }
+
+    // the nodes and items of a well formed rdf:first/rdf:rest chain starting at label
+    fn collection(&self, label: &'a str) -> Option<(Vec<&'a str>, Vec<&'a Term>)> {
+        let mut labels: Vec<&'a str> = Vec::new();
+        let mut items: Vec<&'a Term> = Vec::new();
+        let mut label = label;
+
+        loop {
+            if labels.contains(&label) {
+                return None;
+            }
+
+            let description = &self.descriptions[*self.blank_nodes.get(label)?];
+            let single = |iri: &str| match description.predicates.as_slice() {
+                [a, b] => [a, b]
+                    .into_iter()
+                    .find(|predicate| predicate.iri == iri && predicate.objects.len() == 1)
+                    .map(|predicate| predicate.objects[0]),
+                _ => None,
+            };
+
+            labels.push(label);
+            items.push(single(RDF_FIRST)?);
+
+            match single(RDF_REST)? {
+                Term::NamedNode(NamedNode(iri)) if iri == RDF_NIL => return Some((labels, items)),
+                Term::BlankNode(BlankNode(next))
+                    if self.references.get(next.as_str()) == Some(&1) =>
+                {
+                    label = next
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn compact(&self, iri: &str) -> String {
+        let compacted = self
+            .prefixes
+            .iter()
+            .filter_map(|(name, namespace)| Some((name, iri.strip_prefix(namespace)?)))
+            .filter(|(_, local)| is_local_name(local))
+            .min_by_key(|(_, local)| local.len());
+
+        match compacted {
+            Some((name, local)) => format!("{}{}", name, local),
+            None => ntriples::iri_to_string(iri),
+        }
+    }
+}
+
+// a conservative subset of PN_LOCAL that never needs escaping
+fn is_local_name(local: &str) -> bool {
+    let mut chars = local.chars();
+
+    match chars.next() {
+        Some(c) if is_pn_chars_u(c) || c.is_ascii_digit() => chars.all(is_pn_chars),
+        _ => false,
+    }
+}
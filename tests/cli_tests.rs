@@ -0,0 +1,177 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_turtle_wa"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn parse_file() {
+    let output = run(&["parse", "ttl/simple.ttl"], "");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output).lines().count(), 2);
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Parse time: "));
+}
+
+#[test]
+fn parse_stdin() {
+    let input = "<http://ex/a> <http://ex/b> <http://ex/c> .\n";
+
+    for args in [vec!["parse"], vec!["parse", "-"]] {
+        let output = run(&args, input);
+
+        assert_eq!(output.status.code(), Some(0));
+        assert!(stdout(&output).contains("http://ex/c"));
+    }
+}
+
+#[test]
+fn parse_multiple_files() {
+    let input = "<http://ex/a> <http://ex/b> <http://ex/c> .\n";
+    let output = run(&["parse", "ttl/simple.ttl", "-"], input);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output).lines().count(), 3);
+}
+
+#[test]
+fn validate_invalid_input() {
+    let output = run(&["validate"], "<http://ex/a> foo:b <http://ex/c> .\n");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("undefined prefix"));
+}
+
+#[test]
+fn stats() {
+    let output = run(&["stats", "ttl/simple.ttl"], "");
+    let stats = stdout(&output);
+    let row: Vec<&str> = stats.lines().nth(1).unwrap().split_whitespace().collect();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(row, vec!["ttl/simple.ttl", "2", "1", "1", "2", "0"]);
+}
+
+#[test]
+fn exit_codes() {
+    assert_eq!(run(&[], "").status.code(), Some(2));
+    assert_eq!(run(&["foo"], "").status.code(), Some(2));
+    assert_eq!(run(&["parse", "--foo"], "").status.code(), Some(2));
+    assert_eq!(run(&["parse", "missing.ttl"], "").status.code(), Some(3));
+    assert_eq!(run(&["--help"], "").status.code(), Some(0));
+}
+
+#[test]
+fn convert_to_ntriples() {
+    let input = "@prefix ex: <http://ex/> .\nex:a ex:b \"c\", 1 .\n";
+
+    for args in [vec!["convert"], vec!["convert", "--to", "ntriples", "-"]] {
+        let output = run(&args, input);
+
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(
+            stdout(&output),
+            "<http://ex/a> <http://ex/b> \"c\" .\n\
+             <http://ex/a> <http://ex/b> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n"
+        );
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    }
+
+    assert_eq!(run(&["convert", "--to", "foo"], "").status.code(), Some(2));
+    assert_eq!(
+        run(&["parse", "--to", "ntriples"], "").status.code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn query() {
+    let input = "@prefix ex: <http://ex/> .\nex:a ex:b \"c\", 1 .\n";
+    let output = run(
+        &[
+            "query",
+            "--results",
+            "csv",
+            "--query",
+            "SELECT ?o { ex:a ex:b ?o FILTER(isLiteral(?o)) } ORDER BY ?o",
+        ],
+        input,
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "o\r\n1\r\nc\r\n");
+
+    assert_eq!(run(&["query"], input).status.code(), Some(2));
+    assert_eq!(
+        run(&["query", "--query", "SELECT ?o {"], input)
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        run(&["parse", "--query", "ASK {}"], input).status.code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn serve() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_turtle_wa"))
+        .args(["serve", "--port", "0", "ttl/simple.ttl"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+
+    stderr.read_line(&mut line).unwrap();
+
+    // `Serving 2 triples at http://127.0.0.1:<port>/sparql`
+    let address = line
+        .trim_end()
+        .trim_end_matches("/sparql")
+        .rsplit("http://")
+        .next()
+        .unwrap()
+        .to_string();
+    // a client that never sends its request doesn't hold up the next one
+    let _stalled = TcpStream::connect(&address).unwrap();
+    let mut stream = TcpStream::connect(&address).unwrap();
+    let mut response = String::new();
+
+    stream
+        .write_all(b"GET /sparql?query=ASK+{+?s+?p+?o+} HTTP/1.1\r\nAccept: text/csv\r\n\r\n")
+        .unwrap();
+    stream.read_to_string(&mut response).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(line.starts_with("Serving 2 triples"), "{}", line);
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(response.ends_with("\r\n\r\ntrue\r\n"), "{}", response);
+
+    assert_eq!(run(&["serve", "--port", "http"], "").status.code(), Some(2));
+    assert_eq!(run(&["query", "--port", "1"], "").status.code(), Some(2));
+}
@@ -0,0 +1,91 @@
+use turtle_wa::graph::*;
+use turtle_wa::parser::*;
+use turtle_wa::term::*;
+
+const INPUT: &str = "@prefix ex: <http://example.org/> .
+ex:alice a ex:Person ; ex:knows ex:bob, ex:carol ; ex:name \"Alice\" .
+ex:bob a ex:Person ; ex:knows ex:carol .
+ex:carol ex:knows ex:alice .
+ex:alice ex:knows ex:bob .";
+
+fn ex(name: &str) -> NamedNode {
+    NamedNode(format!("http://example.org/{}", name))
+}
+
+fn load(input: &str) -> Graph {
+    let mut graph = Graph::new();
+
+    parse_reader_into(input.as_bytes(), &mut ParseContext::new(), &mut graph).unwrap();
+
+    graph
+}
+
+fn subjects(triples: impl Iterator<Item = Triple>) -> Vec<String> {
+    triples.map(|triple| triple.subject.to_string()).collect()
+}
+
+#[test]
+fn deduplicate_triples() {
+    let graph = load(INPUT);
+
+    assert_eq!(graph.len(), 7);
+    assert!(graph.contains(&Triple {
+        subject: Subject::from(ex("alice")),
+        predicate: ex("knows"),
+        object: Term::from(ex("bob")),
+    }));
+    assert!(!graph.contains(&Triple {
+        subject: Subject::from(ex("bob")),
+        predicate: ex("knows"),
+        object: Term::from(ex("alice")),
+    }));
+    assert_eq!(graph.iter().collect::<Graph>().len(), 7);
+    assert!(Graph::new().is_empty());
+}
+
+#[test]
+fn match_patterns() {
+    let graph = load(INPUT);
+    let alice = Subject::from(ex("alice"));
+    let carol = Term::from(ex("carol"));
+    let knows = ex("knows");
+    let count = |s, p, o| graph.triples_matching(s, p, o).count();
+
+    assert_eq!(count(Some(&alice), None, None), 4);
+    assert_eq!(count(Some(&alice), Some(&knows), None), 2);
+    assert_eq!(count(Some(&alice), None, Some(&carol)), 1);
+    assert_eq!(count(Some(&alice), Some(&knows), Some(&carol)), 1);
+    assert_eq!(count(None, Some(&knows), None), 4);
+    assert_eq!(count(None, Some(&knows), Some(&carol)), 2);
+    assert_eq!(count(None, None, Some(&carol)), 2);
+    assert_eq!(count(None, None, None), 7);
+    assert_eq!(
+        subjects(graph.triples_matching(None, Some(&NamedNode::new(RDF_TYPE)), None)),
+        ["<http://example.org/alice>", "<http://example.org/bob>"]
+    );
+}
+
+#[test]
+fn unknown_terms_match_nothing() {
+    let graph = load(INPUT);
+    let dave = Subject::from(ex("dave"));
+
+    assert_eq!(graph.triples_matching(Some(&dave), None, None).count(), 0);
+    assert_eq!(
+        graph
+            .triples_matching(
+                None,
+                Some(&ex("name")),
+                Some(&Term::from(Literal::new_simple("Bob")))
+            )
+            .count(),
+        0
+    );
+    // a term that exists, but never as a predicate
+    assert_eq!(
+        graph
+            .triples_matching(None, Some(&ex("alice")), None)
+            .count(),
+        0
+    );
+}
@@ -0,0 +1,100 @@
+use turtle_wa::iri::*;
+
+// the examples of RFC 3986 section 5.4
+const BASE: &str = "http://a/b/c/d;p?q";
+
+#[test]
+fn resolve_normal_examples() {
+    let examples = [
+        ("g:h", "g:h"),
+        ("g", "http://a/b/c/g"),
+        ("./g", "http://a/b/c/g"),
+        ("g/", "http://a/b/c/g/"),
+        ("/g", "http://a/g"),
+        ("//g", "http://g"),
+        ("?y", "http://a/b/c/d;p?y"),
+        ("g?y", "http://a/b/c/g?y"),
+        ("#s", "http://a/b/c/d;p?q#s"),
+        ("g#s", "http://a/b/c/g#s"),
+        ("g?y#s", "http://a/b/c/g?y#s"),
+        (";x", "http://a/b/c/;x"),
+        ("g;x", "http://a/b/c/g;x"),
+        ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+        ("", "http://a/b/c/d;p?q"),
+        (".", "http://a/b/c/"),
+        ("./", "http://a/b/c/"),
+        ("..", "http://a/b/"),
+        ("../", "http://a/b/"),
+        ("../g", "http://a/b/g"),
+        ("../..", "http://a/"),
+        ("../../", "http://a/"),
+        ("../../g", "http://a/g"),
+    ];
+
+    for (reference, expected) in examples {
+        assert_eq!(
+            resolve(BASE, reference),
+            expected,
+            "resolving {}",
+            reference
+        );
+    }
+}
+
+#[test]
+fn resolve_abnormal_examples() {
+    let examples = [
+        ("../../../g", "http://a/g"),
+        ("../../../../g", "http://a/g"),
+        ("/./g", "http://a/g"),
+        ("/../g", "http://a/g"),
+        ("g.", "http://a/b/c/g."),
+        (".g", "http://a/b/c/.g"),
+        ("g..", "http://a/b/c/g.."),
+        ("..g", "http://a/b/c/..g"),
+        ("./../g", "http://a/b/g"),
+        ("./g/.", "http://a/b/c/g/"),
+        ("g/./h", "http://a/b/c/g/h"),
+        ("g/../h", "http://a/b/c/h"),
+        ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+        ("g;x=1/../y", "http://a/b/c/y"),
+        ("g?y/./x", "http://a/b/c/g?y/./x"),
+        ("g?y/../x", "http://a/b/c/g?y/../x"),
+        ("g#s/./x", "http://a/b/c/g#s/./x"),
+        ("g#s/../x", "http://a/b/c/g#s/../x"),
+        ("http:g", "http:g"),
+    ];
+
+    for (reference, expected) in examples {
+        assert_eq!(
+            resolve(BASE, reference),
+            expected,
+            "resolving {}",
+            reference
+        );
+    }
+}
+
+#[test]
+fn resolve_against_base_without_path() {
+    assert_eq!(
+        resolve("http://example.org", "foo"),
+        "http://example.org/foo"
+    );
+    assert_eq!(
+        resolve("http://example.org#x", "#y"),
+        "http://example.org#y"
+    );
+}
+
+#[test]
+fn absolute_iris() {
+    assert!(is_absolute("http://example.org/"));
+    assert!(is_absolute("urn:isbn:0451450523"));
+    assert!(is_absolute("tag+x-1.0:foo"));
+    assert!(!is_absolute("../foo"));
+    assert!(!is_absolute("#frag"));
+    assert!(!is_absolute("foo/bar:baz"));
+    assert!(!is_absolute("1abc:foo"));
+    assert!(!is_absolute(""));
+}
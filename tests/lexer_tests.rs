@@ -1,12 +1,17 @@
 #[cfg(test)]
 use pretty_assertions::assert_eq;
 
+use turtle_wa::diagnostics::*;
 use turtle_wa::lexer::*;
 
+fn lexemes(tokens: Vec<Token>) -> Vec<Lexeme> {
+    tokens.into_iter().map(|token| token.lexeme).collect()
+}
+
 #[test]
 fn parse_base_turtle() {
     let base = "@base <http://example.org/> .";
-    let tokens = tokenize(base, 0, &mut LexerContext::new());
+    let tokens = lexemes(tokenize(base, 0, &mut LexerContext::new()));
 
     assert_eq!(
         tokens,
@@ -20,7 +25,7 @@ fn parse_base_turtle() {
 #[test]
 fn parse_base_sparql() {
     let base = "BASE <http://example.org/> .";
-    let tokens = tokenize(base, 0, &mut LexerContext::new());
+    let tokens = lexemes(tokenize(base, 0, &mut LexerContext::new()));
 
     assert_eq!(
         tokens,
@@ -33,11 +38,11 @@ fn parse_base_sparql() {
 
 #[test]
 fn parse_prefix_turtle_only_colon() {
-    let tokens = tokenize(
+    let tokens = lexemes(tokenize(
         "@prefix : <http://example.org/> .",
         0,
         &mut LexerContext::new(),
-    );
+    ));
 
     assert_eq!(
         tokens,
@@ -50,11 +55,11 @@ fn parse_prefix_turtle_only_colon() {
 
 #[test]
 fn parse_prefix_sparql_only_colon() {
-    let tokens = tokenize(
+    let tokens = lexemes(tokenize(
         "PREFIX : <http://example.org/> .",
         0,
         &mut LexerContext::new(),
-    );
+    ));
 
     assert_eq!(
         tokens,
@@ -67,11 +72,11 @@ fn parse_prefix_sparql_only_colon() {
 
 #[test]
 fn parse_prefix_turtle() {
-    let tokens = tokenize(
+    let tokens = lexemes(tokenize(
         "@prefix foo: <http://example.org/> .",
         0,
         &mut LexerContext::new(),
-    );
+    ));
 
     assert_eq!(
         tokens,
@@ -84,11 +89,11 @@ fn parse_prefix_turtle() {
 
 #[test]
 fn parse_prefix_sparql() {
-    let tokens = tokenize(
+    let tokens = lexemes(tokenize(
         "PREFIX foo: <http://example.org/> .",
         0,
         &mut LexerContext::new(),
-    );
+    ));
 
     assert_eq!(
         tokens,
@@ -100,11 +105,12 @@ fn parse_prefix_sparql() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_object_list_iri() {
     let input = "<http://example.org/subject> <http://example.org/predicate> <http://example.org/object1>, <http://example.org/object2> .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -120,11 +126,12 @@ fn parse_object_list_iri() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_comment() {
     let input = "<http://one.example/subject1> <http://one.example/predicate1> <http://one.example/object1> . # A triple with all absolute IRIs";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -139,11 +146,12 @@ fn parse_comment() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_object_list_literal() {
     let input = "<http://example.org/#spiderman> <http://xmlns.com/foaf/0.1/name> \"Spiderman\", \"Человек-паук\"@ru .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -159,11 +167,12 @@ fn parse_object_list_literal() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_object_list_literal_2() {
     let input = "<http://example.org/#spiderman> <http://xmlns.com/foaf/0.1/name> \"Человек-паук\"@ru, \"Spiderman\" .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -179,11 +188,12 @@ fn parse_object_list_literal_2() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_object_list_literal_mix() {
     let input = "<http://example.org/#spiderman> <http://xmlns.com/foaf/0.1/name> \"Человек-паук\"@ru, <http://example.com/object>, \"Spiderman\"^^<http://www.w3.org/2001/XMLSchema#string> .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -204,6 +214,27 @@ fn parse_object_list_literal_mix() {
 }
 
 #[test]
+fn parse_prefixed_datatype_literal() {
+    let input = "ex:s ex:p \"5\"^^xsd:integer, 'x'^^<http://example.org/t> .";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::PrefixedIri("ex:s".to_string()),
+            Lexeme::PrefixedIri("ex:p".to_string()),
+            Lexeme::PrefixedDataTypeLiteral("5".to_string(), "xsd:integer".to_string()),
+            Lexeme::ObjectListToken,
+            Lexeme::DataTypeLiteral("x".to_string(), "http://example.org/t".to_string()),
+            Lexeme::EndToken,
+        ],
+    );
+}
+
+#[test]
+#[allow(clippy::useless_vec)]
 fn parse_prefixed_uris() {
     let input = vec![
         "@prefix : <http://example.org/> .",
@@ -214,7 +245,7 @@ fn parse_prefixed_uris() {
     .join("\n");
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -239,6 +270,86 @@ fn parse_prefixed_uris() {
 }
 
 #[test]
+fn parse_prefixed_names_without_spaces() {
+    let input = "ex:a:b ex:p ex:o;ex:q ex:name.,ex:x\\,y;a ex:C, _:b0.";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::PrefixedIri("ex:a:b".to_string()),
+            Lexeme::PrefixedIri("ex:p".to_string()),
+            Lexeme::PrefixedIri("ex:o".to_string()),
+            Lexeme::PredicateListToken,
+            Lexeme::PrefixedIri("ex:q".to_string()),
+            Lexeme::PrefixedIri("ex:name".to_string()),
+            Lexeme::EndToken,
+            Lexeme::ObjectListToken,
+            Lexeme::PrefixedIri("ex:x,y".to_string()),
+            Lexeme::PredicateListToken,
+            Lexeme::A,
+            Lexeme::PrefixedIri("ex:C".to_string()),
+            Lexeme::ObjectListToken,
+            Lexeme::BlankNodeLabel("b0".to_string()),
+            Lexeme::EndToken,
+        ],
+    );
+}
+
+#[test]
+fn parse_local_names() {
+    let input = ":0 ex:a.b..c ex:%20x ex:-not ab:c PREFIX:x BASE.foo:bar éx:ü·x ex: \"x\"@en-US.";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::PrefixedIri(":0".to_string()),
+            Lexeme::PrefixedIri("ex:a.b..c".to_string()),
+            Lexeme::PrefixedIri("ex:%20x".to_string()),
+            Lexeme::PrefixedIri("ex:".to_string()),
+            Lexeme::Unknown("-not".to_string()),
+            Lexeme::PrefixedIri("ab:c".to_string()),
+            Lexeme::PrefixedIri("PREFIX:x".to_string()),
+            Lexeme::PrefixedIri("BASE.foo:bar".to_string()),
+            Lexeme::PrefixedIri("éx:ü·x".to_string()),
+            Lexeme::PrefixedIri("ex:".to_string()),
+            Lexeme::LangLiteral("x".to_string(), "en-US".to_string()),
+            Lexeme::EndToken,
+        ],
+    );
+}
+
+#[test]
+fn invalid_local_names() {
+    let input = "ex:a ex:%2x ex:b\\q .";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 1, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![Lexeme::PrefixedIri("ex:a".to_string()), Lexeme::EndToken]
+    );
+    assert_eq!(
+        lexer_context
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.message.as_str(), diagnostic.span.start))
+            .collect::<Vec<_>>(),
+        vec![
+            ("invalid percent encoding in local name, expected `%XX`", 5),
+            ("invalid escape sequence in local name", 12),
+        ]
+    );
+}
+
+#[test]
+#[allow(clippy::useless_vec)]
 fn parse_predicate_list() {
     let input = vec![
         "<http://example.org/#spiderman> <http://www.perceive.net/schemas/relationship/enemyOf> <http://example.org/#green-goblin> ;",
@@ -247,7 +358,7 @@ fn parse_predicate_list() {
         .join("\n");
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -267,6 +378,114 @@ fn parse_predicate_list() {
 }
 
 #[test]
+fn parse_numbers() {
+    let input = "ex:p 5, -5., +.5e-3, 1.E7;ex:q 4.002602.5 12abc .";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::PrefixedIri("ex:p".to_string()),
+            Lexeme::Unknown("5".to_string()),
+            Lexeme::ObjectListToken,
+            Lexeme::Unknown("-5".to_string()),
+            Lexeme::EndToken,
+            Lexeme::ObjectListToken,
+            Lexeme::Unknown("+.5e-3".to_string()),
+            Lexeme::ObjectListToken,
+            Lexeme::Unknown("1.E7".to_string()),
+            Lexeme::PredicateListToken,
+            Lexeme::PrefixedIri("ex:q".to_string()),
+            Lexeme::Unknown("4.002602".to_string()),
+            Lexeme::Unknown(".5".to_string()),
+            Lexeme::Unknown("12abc".to_string()),
+            Lexeme::EndToken,
+        ],
+    );
+}
+
+#[test]
+fn parse_graphs() {
+    let input = "GRAPH ex:g{ex:a ex:p ex:b}graph <g> { }";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::Graph,
+            Lexeme::PrefixedIri("ex:g".to_string()),
+            Lexeme::GraphStartToken,
+            Lexeme::PrefixedIri("ex:a".to_string()),
+            Lexeme::PrefixedIri("ex:p".to_string()),
+            Lexeme::PrefixedIri("ex:b".to_string()),
+            Lexeme::GraphEndToken,
+            Lexeme::Graph,
+            Lexeme::Iri("g".to_string()),
+            Lexeme::GraphStartToken,
+            Lexeme::GraphEndToken,
+        ],
+    );
+}
+
+#[test]
+fn parse_variables() {
+    let input = "?s $p ?o1.?_x";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::Variable("s".to_string()),
+            Lexeme::Variable("p".to_string()),
+            Lexeme::Variable("o1".to_string()),
+            Lexeme::EndToken,
+            Lexeme::Variable("_x".to_string()),
+        ],
+    );
+}
+
+#[test]
+fn parse_operators() {
+    let input = "?a<=1 &&!(?b != ?c)||?d > 2 * 3 < <http://ex/>";
+    let mut lexer_context = LexerContext::new();
+
+    lexer_context.query = true;
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::Variable("a".to_string()),
+            Lexeme::Operator("<=".to_string()),
+            Lexeme::Unknown("1".to_string()),
+            Lexeme::Operator("&&".to_string()),
+            Lexeme::Operator("!".to_string()),
+            Lexeme::CollectionStartToken,
+            Lexeme::Variable("b".to_string()),
+            Lexeme::Operator("!=".to_string()),
+            Lexeme::Variable("c".to_string()),
+            Lexeme::CollectionEndToken,
+            Lexeme::Operator("||".to_string()),
+            Lexeme::Variable("d".to_string()),
+            Lexeme::Operator(">".to_string()),
+            Lexeme::Unknown("2".to_string()),
+            Lexeme::Operator("*".to_string()),
+            Lexeme::Unknown("3".to_string()),
+            Lexeme::Operator("<".to_string()),
+            Lexeme::Iri("http://ex/".to_string()),
+        ],
+    );
+}
+
+#[test]
+#[allow(clippy::useless_vec)]
 fn parse_boolean() {
     let input = vec![
         "@prefix : <http://example.org/stats> .",
@@ -276,7 +495,7 @@ fn parse_boolean() {
     .join("\n");
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -293,12 +512,13 @@ fn parse_boolean() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_integer() {
     let input =
         "<http://somecountry.example/census2007> <http://example.org/stats/population> 1234567890 .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -312,12 +532,13 @@ fn parse_integer() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_negative_integer() {
     let input =
         "<http://somecountry.example/census2007> <http://example.org/stats/population> -1234567890 .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -331,12 +552,13 @@ fn parse_negative_integer() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_decimal() {
     let input =
         "<http://somecountry.example/census2007> <http://example.org/stats/area> 4.002602 .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -350,12 +572,13 @@ fn parse_decimal() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_negative_decimal() {
     let input =
         "<http://somecountry.example/census2007> <http://example.org/stats/area> -4.002602 .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -369,12 +592,13 @@ fn parse_negative_decimal() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_double() {
     let input =
         "<http://somecountry.example/census2007> <http://example.org/stats/gravity> 1.663E-4 .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -388,12 +612,13 @@ fn parse_double() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn parse_negative_double() {
     let input =
         "<http://somecountry.example/census2007> <http://example.org/stats/gravity> -1.663E-4 .";
     let mut lexer_context = LexerContext::new();
 
-    let tokens = tokenize(&input, 0, &mut lexer_context);
+    let tokens = lexemes(tokenize(&input, 0, &mut lexer_context));
 
     assert_eq!(
         tokens,
@@ -407,6 +632,7 @@ fn parse_negative_double() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow, clippy::explicit_counter_loop)]
 fn parse_multiline() {
     let input = vec![
         "<http://somecountry.example/census2007> <http://example.org/stats/gravity> \"\"\"hello multi",
@@ -419,7 +645,7 @@ fn parse_multiline() {
     let mut lexer_context = LexerContext::new();
 
     for line in input {
-        let mut line_tokens = tokenize(&line, i, &mut lexer_context);
+        let mut line_tokens = lexemes(tokenize(&line, i, &mut lexer_context));
         tokens.append(&mut line_tokens);
         i += 1;
     }
@@ -436,6 +662,7 @@ fn parse_multiline() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow, clippy::explicit_counter_loop)]
 fn parse_short_multiline() {
     let input = vec![
         "<http://somecountry.example/census2007> <http://example.org/stats/gravity> \"\"\"short multi line\"\"\" .",
@@ -446,7 +673,7 @@ fn parse_short_multiline() {
     let mut lexer_context = LexerContext::new();
 
     for line in input {
-        let mut line_tokens = tokenize(&line, i, &mut lexer_context);
+        let mut line_tokens = lexemes(tokenize(&line, i, &mut lexer_context));
         tokens.append(&mut line_tokens);
         i += 1;
     }
@@ -463,6 +690,7 @@ fn parse_short_multiline() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow, clippy::explicit_counter_loop)]
 fn parse_multiple_multilines() {
     let input = vec![
         "<http://somecountry.example/census2007> <http://example.org/stats/gravity> \"\"\"hello multi",
@@ -478,7 +706,7 @@ fn parse_multiple_multilines() {
     let mut lexer_context = LexerContext::new();
 
     for line in input {
-        let mut line_tokens = tokenize(&line, i, &mut lexer_context);
+        let mut line_tokens = lexemes(tokenize(&line, i, &mut lexer_context));
         tokens.append(&mut line_tokens);
         i += 1;
     }
@@ -497,3 +725,475 @@ fn parse_multiple_multilines() {
         ],
     );
 }
+
+#[test]
+fn parse_blank_node_labels() {
+    let input = "_:alice <http://xmlns.com/foaf/0.1/knows> _:b0 .";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::BlankNodeLabel("alice".to_string()),
+            Lexeme::Iri("http://xmlns.com/foaf/0.1/knows".to_string()),
+            Lexeme::BlankNodeLabel("b0".to_string()),
+            Lexeme::EndToken,
+        ],
+    );
+}
+
+#[test]
+fn parse_blank_node_property_list() {
+    let input = "ex:alice foaf:knows [ foaf:name \"Bob\" ; foaf:age 30 ], [].";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::PrefixedIri("ex:alice".to_string()),
+            Lexeme::PrefixedIri("foaf:knows".to_string()),
+            Lexeme::BlankNodeStartToken,
+            Lexeme::PrefixedIri("foaf:name".to_string()),
+            Lexeme::Literal("Bob".to_string()),
+            Lexeme::PredicateListToken,
+            Lexeme::PrefixedIri("foaf:age".to_string()),
+            Lexeme::Unknown("30".to_string()),
+            Lexeme::BlankNodeEndToken,
+            Lexeme::ObjectListToken,
+            Lexeme::BlankNodeStartToken,
+            Lexeme::BlankNodeEndToken,
+            Lexeme::EndToken,
+        ],
+    );
+}
+
+#[test]
+fn parse_collection() {
+    let input = "ex:list ex:items (ex:a \"b\" 3) , ().";
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(input, 0, &mut lexer_context));
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::PrefixedIri("ex:list".to_string()),
+            Lexeme::PrefixedIri("ex:items".to_string()),
+            Lexeme::CollectionStartToken,
+            Lexeme::PrefixedIri("ex:a".to_string()),
+            Lexeme::Literal("b".to_string()),
+            Lexeme::Unknown("3".to_string()),
+            Lexeme::CollectionEndToken,
+            Lexeme::ObjectListToken,
+            Lexeme::CollectionStartToken,
+            Lexeme::CollectionEndToken,
+            Lexeme::EndToken,
+        ],
+    );
+}
+
+#[test]
+fn token_spans() {
+    let input = "<http://example.org/s> foaf:name \"Паук\"@ru .";
+    let tokens = tokenize(input, 7, &mut LexerContext::new());
+
+    let spans: Vec<(usize, usize, usize)> = tokens
+        .iter()
+        .map(|token| (token.span.start, token.span.end, token.span.offset))
+        .collect();
+
+    assert!(tokens.iter().all(|token| token.span.line == 7));
+    assert_eq!(
+        spans,
+        vec![(0, 22, 0), (23, 32, 23), (33, 42, 33), (43, 44, 47)]
+    );
+}
+
+#[test]
+fn multiline_token_span_points_to_start() {
+    let input = vec![
+        "<http://example.org/s> <http://example.org/p> \"\"\"hello",
+        "multi line\"\"\" .",
+    ];
+    let mut tokens: Vec<Token> = vec![];
+    let mut lexer_context = LexerContext::new();
+
+    for (i, line) in (1..).zip(input) {
+        tokens.append(&mut tokenize(line, i, &mut lexer_context));
+    }
+
+    assert_eq!(
+        tokens[2],
+        Token {
+            lexeme: Lexeme::MultilineLiteral("hello\nmulti line".to_string()),
+            span: Span {
+                line: 1,
+                start: 46,
+                end: 54,
+                offset: 46,
+            },
+        }
+    );
+    assert_eq!(tokens[3].span.line, 2);
+    assert_eq!(tokens[3].span.start, 14);
+}
+
+#[test]
+fn collect_diagnostics() {
+    let mut lexer_context = LexerContext::new();
+
+    let tokens = lexemes(tokenize(
+        "<http://example.org/s> <http://example.org/p> <http://unterminated .",
+        1,
+        &mut lexer_context,
+    ));
+    tokenize("@prefix ex <http://example.org/> .", 2, &mut lexer_context);
+    tokenize(
+        "<http://example.org/s> <http://example.org/p> \"\"\"open",
+        3,
+        &mut lexer_context,
+    );
+    finish(&mut lexer_context);
+
+    assert_eq!(
+        tokens,
+        vec![
+            Lexeme::Iri("http://example.org/s".to_string()),
+            Lexeme::Iri("http://example.org/p".to_string()),
+        ],
+    );
+    assert_eq!(
+        lexer_context.diagnostics,
+        vec![
+            Diagnostic::error(
+                "unterminated iri, missing `>`",
+                Span {
+                    line: 1,
+                    start: 46,
+                    end: 68,
+                    offset: 46,
+                },
+            ),
+            Diagnostic::error(
+                "expected prefix name ending with `:`",
+                Span {
+                    line: 2,
+                    start: 0,
+                    end: 10,
+                    offset: 0,
+                },
+            ),
+            Diagnostic::error(
+                "unterminated multiline literal, missing `\"\"\"`",
+                Span {
+                    line: 3,
+                    start: 46,
+                    end: 53,
+                    offset: 46,
+                },
+            ),
+        ],
+    );
+}
+
+// tokenizes the input line by line, the way the line api is used
+fn tokenize_lines(input: &str) -> (Vec<Token>, Vec<Diagnostic>) {
+    let mut lexer_context = LexerContext::new();
+    let mut tokens: Vec<Token> = vec![];
+
+    for (i, line) in (1..).zip(input.lines()) {
+        tokens.append(&mut tokenize(line, i, &mut lexer_context));
+    }
+
+    finish(&mut lexer_context);
+
+    (tokens, lexer_context.diagnostics)
+}
+
+fn tokenize_stream(input: &str) -> (Vec<Token>, Vec<Diagnostic>) {
+    let mut lexer = Lexer::from(input);
+    let tokens = lexer.by_ref().map(Result::unwrap).collect();
+
+    (tokens, lexer.take_diagnostics())
+}
+
+// byte offsets are relative to the line in the line api and to the input in the stream
+fn without_offsets(
+    (tokens, diagnostics): (Vec<Token>, Vec<Diagnostic>),
+) -> (Vec<Token>, Vec<Diagnostic>) {
+    let tokens = tokens
+        .into_iter()
+        .map(|token| Token {
+            span: Span {
+                offset: 0,
+                ..token.span
+            },
+            ..token
+        })
+        .collect();
+    let diagnostics = diagnostics
+        .into_iter()
+        .map(|diagnostic| Diagnostic {
+            span: Span {
+                offset: 0,
+                ..diagnostic.span
+            },
+            ..diagnostic
+        })
+        .collect();
+
+    (tokens, diagnostics)
+}
+
+#[test]
+fn stream_matches_line_api() {
+    let inputs = [
+        "@base <http://example.org/> .\nPREFIX foaf: <http://xmlns.com/foaf/0.1/>\n",
+        "<http://example.org/s> foaf:name \"Паук\"@ru ; foaf:age 30 , -1.5e3 . # comment\n",
+        "_:b0 <http://example.org/p> [ foaf:name \"a\" ] , ( 1 2.5 true ) .",
+        "<s> <p> \"\"\"hello\n\n line \"\" literal\"\"\" , \"\"\"short\"\"\" .\n<s> <p> \"\"\"two\nlines\"\"\" .",
+        "<s> <p> <http://unterminated .\n@prefix ex <http://example.org/> .\n<s> <p> 'single' .\n<s> <p> \"\"\"open",
+    ];
+
+    for input in inputs {
+        assert_eq!(
+            without_offsets(tokenize_stream(input)),
+            without_offsets(tokenize_lines(input))
+        );
+    }
+}
+
+#[test]
+fn stream_tokens_across_lines() {
+    let input = "@prefix\n  foaf: <http://xmlns.com/foaf/0.1/> .\n<http://example.org/s>\n\tfoaf:name\n\t\t\"\"\"Паук\nмульти\"\"\" .";
+    let (tokens, diagnostics) = tokenize_stream(input);
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(
+        tokens,
+        vec![
+            Token {
+                lexeme: Lexeme::Prefix(
+                    "foaf:".to_string(),
+                    "http://xmlns.com/foaf/0.1/".to_string()
+                ),
+                span: Span {
+                    line: 1,
+                    start: 0,
+                    end: 7,
+                    offset: 0,
+                },
+            },
+            Token {
+                lexeme: Lexeme::EndToken,
+                span: Span {
+                    line: 2,
+                    start: 37,
+                    end: 38,
+                    offset: 45,
+                },
+            },
+            Token {
+                lexeme: Lexeme::Iri("http://example.org/s".to_string()),
+                span: Span {
+                    line: 3,
+                    start: 0,
+                    end: 22,
+                    offset: 47,
+                },
+            },
+            Token {
+                lexeme: Lexeme::PrefixedIri("foaf:name".to_string()),
+                span: Span {
+                    line: 4,
+                    start: 1,
+                    end: 10,
+                    offset: 71,
+                },
+            },
+            Token {
+                lexeme: Lexeme::MultilineLiteral("Паук\nмульти".to_string()),
+                span: Span {
+                    line: 5,
+                    start: 2,
+                    end: 9,
+                    offset: 83,
+                },
+            },
+            Token {
+                lexeme: Lexeme::EndToken,
+                span: Span {
+                    line: 6,
+                    start: 10,
+                    end: 11,
+                    offset: 111,
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn stream_reports_read_errors() {
+    let input: &[u8] = b"<http://example.org/s> \xff\xfe .";
+    let result: Result<Vec<Token>, _> = Lexer::new(input).collect();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn decode_literal_escapes() {
+    let input = r#""tab\tnew\nline\r\b\f \"quoted\" \'single\' back\\slash" "é\U0001F600" """multi \""" line\"""" ."#;
+    let (tokens, diagnostics) = tokenize_stream(input);
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(
+        lexemes(tokens),
+        vec![
+            Lexeme::Literal(
+                "tab\tnew\nline\r\u{08}\u{0C} \"quoted\" 'single' back\\slash".to_string()
+            ),
+            Lexeme::Literal("é😀".to_string()),
+            Lexeme::MultilineLiteral("multi \"\"\" line\"".to_string()),
+            Lexeme::EndToken,
+        ]
+    );
+}
+
+#[test]
+fn decode_iri_escapes() {
+    let input = r"<http://example.org/été> <http://example.org/a\U0000003Eb> .";
+    let (tokens, diagnostics) = tokenize_stream(input);
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(
+        lexemes(tokens),
+        vec![
+            Lexeme::Iri("http://example.org/été".to_string()),
+            Lexeme::Iri("http://example.org/a>b".to_string()),
+            Lexeme::EndToken,
+        ]
+    );
+}
+
+#[test]
+fn invalid_escapes() {
+    let input = r#""a\qb" "\u00zz" "\uD800" <http://example.org/\n> "\U00110000" ."#;
+    let (tokens, diagnostics) = tokenize_stream(input);
+
+    assert_eq!(
+        lexemes(tokens),
+        vec![
+            Lexeme::Unknown("\"aqb\"".to_string()),
+            Lexeme::Unknown("\"zz\"".to_string()),
+            Lexeme::Unknown("\"\"".to_string()),
+            Lexeme::Unknown("<http://example.org/n>".to_string()),
+            Lexeme::Unknown("\"\"".to_string()),
+            Lexeme::EndToken,
+        ]
+    );
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|diagnostic| (
+                diagnostic.message.as_str(),
+                diagnostic.span.start,
+                diagnostic.span.end
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("invalid escape sequence `\\q`", 2, 3),
+            (
+                "invalid escape sequence `\\u00`, expected 4 hex digits",
+                8,
+                12
+            ),
+            ("invalid code point `\\uD800` in escape sequence", 17, 23),
+            ("invalid escape sequence `\\n`", 45, 46),
+            (
+                "invalid code point `\\U00110000` in escape sequence",
+                50,
+                60
+            ),
+        ]
+    );
+}
+
+#[test]
+fn invalid_iri_characters() {
+    let input = r#"<http://ex/a b> <http://ex/{c}> <http://ex/d\u0041> <http://ex/e\n> ."#;
+    let (tokens, diagnostics) = tokenize_stream(input);
+
+    assert_eq!(
+        lexemes(tokens),
+        vec![
+            Lexeme::Iri("http://ex/dA".to_string()),
+            Lexeme::Unknown("<http://ex/en>".to_string()),
+            Lexeme::EndToken,
+        ]
+    );
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|diagnostic| (
+                diagnostic.message.as_str(),
+                diagnostic.span.start,
+                diagnostic.span.end
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("invalid character in iri", 0, 15),
+            ("invalid character in iri", 16, 31),
+            ("invalid escape sequence `\\n`", 64, 65),
+        ]
+    );
+}
+
+#[test]
+fn parse_single_quoted_literals() {
+    let input = r#"'single' 'it''s'@en '5'^^<http://www.w3.org/2001/XMLSchema#integer> '''long 'quoted'
+''' """long"""@en '''with "double" quotes'''^^<http://example.org/t> 'say "hi"' ."#;
+    let expected = vec![
+        Lexeme::Literal("single".to_string()),
+        Lexeme::Literal("it".to_string()),
+        Lexeme::LangLiteral("s".to_string(), "en".to_string()),
+        Lexeme::DataTypeLiteral(
+            "5".to_string(),
+            "http://www.w3.org/2001/XMLSchema#integer".to_string(),
+        ),
+        Lexeme::MultilineLiteral("long 'quoted'\n".to_string()),
+        Lexeme::LangLiteral("long".to_string(), "en".to_string()),
+        Lexeme::DataTypeLiteral(
+            "with \"double\" quotes".to_string(),
+            "http://example.org/t".to_string(),
+        ),
+        Lexeme::Literal("say \"hi\"".to_string()),
+        Lexeme::EndToken,
+    ];
+    let (stream_tokens, stream_diagnostics) = tokenize_stream(input);
+    let (line_tokens, line_diagnostics) = tokenize_lines(input);
+
+    assert_eq!(stream_diagnostics, vec![]);
+    assert_eq!(line_diagnostics, vec![]);
+    assert_eq!(lexemes(stream_tokens), expected);
+    assert_eq!(lexemes(line_tokens), expected);
+}
+
+#[test]
+fn unterminated_single_quoted_literals() {
+    let (tokens, _) = tokenize_stream("'open .");
+    let (_, diagnostics) = tokenize_stream("<s> <p> '''open");
+
+    assert_eq!(
+        lexemes(tokens),
+        vec![Lexeme::Unknown("'open .".to_string())]
+    );
+    assert_eq!(
+        diagnostics[0].message,
+        "unterminated multiline literal, missing `'''`"
+    );
+}
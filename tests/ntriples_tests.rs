@@ -0,0 +1,267 @@
+use turtle_wa::ntriples::*;
+use turtle_wa::parser::ParseError;
+use turtle_wa::term::*;
+
+fn triple(object: Term) -> Triple {
+    Triple {
+        subject: Subject::NamedNode(NamedNode("http://example.org/s".to_string())),
+        predicate: NamedNode("http://example.org/p".to_string()),
+        object,
+    }
+}
+
+fn typed(value: &str, datatype: &str) -> Term {
+    Term::from(Literal::new_typed(value, datatype))
+}
+
+fn write(triples: &[Triple]) -> String {
+    let mut out: Vec<u8> = Vec::new();
+
+    write_triples(&mut out, triples).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn write_iris_and_blank_nodes() {
+    let triples = vec![
+        triple(Term::NamedNode(NamedNode(
+            "http://example.org/o".to_string(),
+        ))),
+        Triple {
+            subject: Subject::BlankNode(BlankNode("d0b0".to_string())),
+            predicate: NamedNode("http://example.org/p".to_string()),
+            object: Term::BlankNode(BlankNode("d0b1".to_string())),
+        },
+    ];
+
+    assert_eq!(
+        write(&triples),
+        "<http://example.org/s> <http://example.org/p> <http://example.org/o> .\n\
+         _:d0b0 <http://example.org/p> _:d0b1 .\n"
+    );
+}
+
+#[test]
+fn escape_literals() {
+    let input = [
+        (
+            Term::Literal(Literal::new_simple("plain".to_string())),
+            r#""plain""#,
+        ),
+        (
+            Term::Literal(Literal::new_simple("say \"hi\"\\".to_string())),
+            r#""say \"hi\"\\""#,
+        ),
+        (
+            Term::Literal(Literal::new_simple("multi\nline\r\ttab".to_string())),
+            r#""multi\nline\r\ttab""#,
+        ),
+        (
+            Term::Literal(Literal::new_simple("bell\u{07}".to_string())),
+            r#""bell\u0007""#,
+        ),
+        (
+            Term::Literal(Literal::new_simple("back\u{08}feed\u{0C}".to_string())),
+            r#""back\bfeed\f""#,
+        ),
+        (
+            Term::Literal(Literal::new_simple("escape\u{1B}delete\u{7F}".to_string())),
+            r#""escape\u001Bdelete\u007F""#,
+        ),
+        (
+            Term::Literal(Literal::new_simple("Человек".to_string())),
+            r#""Человек""#,
+        ),
+    ];
+
+    for (object, expected) in input {
+        assert_eq!(object.to_string(), expected);
+    }
+}
+
+#[test]
+fn escape_iris() {
+    let object = Term::NamedNode(NamedNode("http://example.org/a b>".to_string()));
+
+    assert_eq!(object.to_string(), r"<http://example.org/a\u0020b\u003E>");
+}
+
+#[test]
+fn write_typed_literals() {
+    let input = [
+        (
+            typed("true", XSD_BOOLEAN),
+            r#""true"^^<http://www.w3.org/2001/XMLSchema#boolean>"#,
+        ),
+        (
+            typed("-5", XSD_INTEGER),
+            r#""-5"^^<http://www.w3.org/2001/XMLSchema#integer>"#,
+        ),
+        (
+            typed("4.5", XSD_DECIMAL),
+            r#""4.5"^^<http://www.w3.org/2001/XMLSchema#decimal>"#,
+        ),
+        (
+            typed("+04.50", XSD_DECIMAL),
+            r#""+04.50"^^<http://www.w3.org/2001/XMLSchema#decimal>"#,
+        ),
+        (
+            typed("1.663E-4", XSD_DOUBLE),
+            r#""1.663E-4"^^<http://www.w3.org/2001/XMLSchema#double>"#,
+        ),
+        (
+            Term::Literal(Literal::new_typed(
+                "2".to_string(),
+                "http://www.w3.org/2001/XMLSchema#byte".to_string(),
+            )),
+            r#""2"^^<http://www.w3.org/2001/XMLSchema#byte>"#,
+        ),
+        (
+            Term::Literal(Literal::new_typed(
+                "s".to_string(),
+                "http://www.w3.org/2001/XMLSchema#string".to_string(),
+            )),
+            r#""s""#,
+        ),
+    ];
+
+    for (object, expected) in input {
+        assert_eq!(object.to_string(), expected);
+    }
+}
+
+#[test]
+fn keep_language_tags() {
+    let object = Term::Literal(Literal::new_language_tagged(
+        "Человек-паук".to_string(),
+        "ru".to_string(),
+    ));
+
+    assert_eq!(object.to_string(), r#""Человек-паук"@ru"#);
+}
+
+#[test]
+fn write_to_writer() {
+    let triples = vec![triple(typed("1", XSD_INTEGER))];
+    let mut out: Vec<u8> = Vec::new();
+
+    write_triples(&mut out, &triples).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "<http://example.org/s> <http://example.org/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n"
+    );
+}
+
+fn errors(input: &str) -> Vec<(u32, String)> {
+    NQuadsParser::from(input)
+        .filter_map(|result| result.err())
+        .map(|error| (error.line(), error.message()))
+        .collect()
+}
+
+#[test]
+fn parse_triples() {
+    let input = "# a dump
+<http://example.org/s> <http://example.org/p> <http://example.org/o> .
+
+_:b0 <http://example.org/p> \"say \\\"hi\\\"\\n\" . # trailing comment
+<http://example.org/s> <http://example.org/p> \"chat\"@FR .
+<http://example.org/s> <http://example.org/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer>.
+_:b0 <http://example.org/p> _:b1 .";
+    let triples: Result<Vec<Triple>, ParseError> = NTriplesParser::from(input).collect();
+    let triples = triples.unwrap();
+
+    assert_eq!(
+        triples[..4],
+        [
+            triple(Term::from(NamedNode::new("http://example.org/o"))),
+            Triple {
+                subject: triples[1].subject.clone(),
+                ..triple(Term::from(Literal::new_simple("say \"hi\"\n")))
+            },
+            triple(Term::from(Literal::new_language_tagged("chat", "fr"))),
+            triple(typed("1", XSD_INTEGER)),
+        ]
+    );
+    assert_eq!(triples[4].subject, triples[1].subject);
+    assert_ne!(Term::from(triples[4].subject.clone()), triples[4].object);
+
+    // what was parsed is written back the same way
+    assert_eq!(
+        write(&triples[..1]),
+        "<http://example.org/s> <http://example.org/p> <http://example.org/o> .\n"
+    );
+}
+
+#[test]
+fn parse_quads() {
+    let input = "<http://example.org/s> <http://example.org/p> \"o\" <http://example.org/g> .
+<http://example.org/s> <http://example.org/p> \"o\" .
+<http://example.org/s> <http://example.org/p> \"o\" _:g .";
+    let quads: Vec<Quad> = NQuadsParser::from(input).map(Result::unwrap).collect();
+
+    assert_eq!(
+        quads[0].to_string(),
+        "<http://example.org/s> <http://example.org/p> \"o\" <http://example.org/g> ."
+    );
+    assert_eq!(
+        quads[1],
+        Quad::from(triple(Term::from(Literal::new_simple("o"))))
+    );
+    assert!(matches!(quads[2].graph_name, Some(GraphName::BlankNode(_))));
+}
+
+#[test]
+fn reject_turtle_shortcuts() {
+    let input = "@prefix ex: <http://example.org/> .
+ex:s <http://example.org/p> <http://example.org/o> .
+<http://example.org/s> a <http://example.org/o> .
+<http://example.org/s> <http://example.org/p> ( 1 ) .
+<http://example.org/s> <http://example.org/p> 'single' .
+<http://example.org/s> <http://example.org/p> 42 .
+<http://example.org/s> <http://example.org/p> <o> .
+<http://example.org/s> <http://example.org/p> <http://example.org/a>, <http://example.org/b> .
+<http://example.org/s> <http://example.org/p> <http://example.org/o>
+<http://example.org/s> <http://example.org/p> \"\"\"long\"\"\" .
+<http://example.org/s> <http://example.org/p> \"\"\"long\"\"\"@en .
+<http://example.org/s> <http://example.org/p> \"\"\"long\"\"\"^^<http://example.org/d> .";
+
+    assert_eq!(
+        errors(input),
+        [
+            (
+                1,
+                "unexpected token `@prefix ex: <http://example.org/>`".to_string()
+            ),
+            (2, "unexpected token `ex:s`".to_string()),
+            (3, "unexpected token `a`".to_string()),
+            (4, "unexpected token `(`".to_string()),
+            (5, "unexpected token `'single'`".to_string()),
+            (6, "unexpected token `42`".to_string()),
+            (7, "relative iri `<o>` without @base".to_string()),
+            (8, "unexpected token `,`".to_string()),
+            (
+                9,
+                "unexpected end of input, statement is missing `.`".to_string()
+            ),
+            (10, "unexpected token `\"\"\"long\"\"\"`".to_string()),
+            (11, "unexpected token `\"\"\"long\"\"\"@en`".to_string()),
+            (
+                12,
+                "unexpected token `\"\"\"long\"\"\"^^<http://example.org/d>`".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn graph_names_are_n_quads_only() {
+    let input = "<http://example.org/s> <http://example.org/p> <http://example.org/o> <http://example.org/g> .";
+    let results: Vec<Result<Triple, ParseError>> = NTriplesParser::from(input).collect();
+
+    assert_eq!(
+        results[0].as_ref().map_err(|error| error.message()),
+        Err("unexpected token `<http://example.org/g>`".to_string())
+    );
+}
@@ -0,0 +1,75 @@
+use turtle_wa::number::*;
+
+fn decimal(lexical: &str) -> BigDecimal {
+    BigDecimal::parse(lexical).unwrap()
+}
+
+#[test]
+fn classify_lexical_forms() {
+    let numbers = [
+        ("1", true, false, false),
+        ("-01", true, false, false),
+        ("+1.5", false, true, false),
+        (".5", false, true, false),
+        ("5.", false, false, false),
+        ("1e5", false, false, true),
+        ("1.E-5", false, false, true),
+        (".5e+5", false, false, true),
+        ("e5", false, false, false),
+        ("1.5e", false, false, false),
+        ("+", false, false, false),
+        ("1_000", false, false, false),
+        ("1E400", false, false, true),
+    ];
+
+    for (lexical, integer, decimal, double) in numbers {
+        assert_eq!(is_integer(lexical), integer, "{}", lexical);
+        assert_eq!(is_decimal(lexical), decimal, "{}", lexical);
+        assert_eq!(is_double(lexical), double, "{}", lexical);
+    }
+}
+
+#[test]
+fn big_integers() {
+    assert_eq!(
+        BigInt::parse("-000123456789012345678901234567890")
+            .unwrap()
+            .to_string(),
+        "-123456789012345678901234567890"
+    );
+    assert_eq!(BigInt::parse("-0").unwrap(), BigInt::from(0));
+    assert_eq!(BigInt::parse("+42").unwrap().to_i64(), Some(42));
+    assert_eq!(BigInt::from(i64::MIN).to_i64(), Some(i64::MIN));
+
+    let mut values: Vec<BigInt> = ["10", "-2", "9", "-10", "0", "123456789012345678901"]
+        .iter()
+        .map(|lexical| BigInt::parse(lexical).unwrap())
+        .collect();
+
+    values.sort();
+
+    assert_eq!(
+        values.iter().map(BigInt::to_string).collect::<Vec<_>>(),
+        ["-10", "-2", "0", "9", "10", "123456789012345678901"]
+    );
+}
+
+#[test]
+fn big_decimals() {
+    assert_eq!(decimal("4.002602").to_string(), "4.002602");
+    assert_eq!(decimal("+04.500").to_string(), "4.5");
+    assert_eq!(decimal("-.005").to_string(), "-0.005");
+    assert_eq!(decimal("12").to_string(), "12.0");
+    assert_eq!(decimal("-0.0").to_string(), "0.0");
+    assert_eq!(
+        decimal("123456789012345678901234567890.000000000000000000001").to_string(),
+        "123456789012345678901234567890.000000000000000000001"
+    );
+
+    assert_eq!(decimal("1.50"), decimal("1.5"));
+    assert!(decimal("1.05") < decimal("1.5"));
+    assert!(decimal("-1.5") < decimal("-1.05"));
+    assert!(decimal("2") > decimal("1.999999999999999999999"));
+    assert_eq!(BigDecimal::from(BigInt::from(3)), decimal("3.0"));
+    assert_eq!(decimal("0.1").to_f64(), 0.1);
+}
@@ -3,8 +3,18 @@
 
 use std::collections::HashMap;
 
+use turtle_wa::diagnostics::*;
 use turtle_wa::lexer::*;
 use turtle_wa::parser::*;
+use turtle_wa::term::*;
+
+fn tokens(lexemes: &[Lexeme]) -> Vec<Token> {
+    lexemes.iter().cloned().map(Token::from).collect()
+}
+
+fn typed(value: &str, datatype: &str) -> Term {
+    Term::from(Literal::new_typed(value, datatype))
+}
 
 mod parser {
     mod parse_triple {
@@ -19,14 +29,16 @@ mod parser {
                 Lexeme::EndToken,
             ];
             let mut context = ParseContext::new();
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
             assert_eq!(
                 triples,
                 vec![Triple {
-                    subject: Iri("http://example.org/subject".to_string()),
-                    predicate: Iri("http://example.org/predicate".to_string()),
-                    object: Object::Iri("http://example.org/object".to_string()),
+                    subject: Subject::NamedNode(NamedNode(
+                        "http://example.org/subject".to_string()
+                    )),
+                    predicate: NamedNode("http://example.org/predicate".to_string()),
+                    object: Term::NamedNode(NamedNode("http://example.org/object".to_string())),
                 }]
             );
         }
@@ -40,14 +52,18 @@ mod parser {
                 Lexeme::EndToken,
             ];
             let mut context = ParseContext::new();
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
             assert_eq!(
                 triples,
                 vec![Triple {
-                    subject: Iri("http://example.org/subject".to_string()),
-                    predicate: Iri("http://www.w3.org/1999/02/22-rdf-syntax-ns#type".to_string()),
-                    object: Object::Iri("http://example.org/object".to_string()),
+                    subject: Subject::NamedNode(NamedNode(
+                        "http://example.org/subject".to_string()
+                    )),
+                    predicate: NamedNode(
+                        "http://www.w3.org/1999/02/22-rdf-syntax-ns#type".to_string()
+                    ),
+                    object: Term::NamedNode(NamedNode("http://example.org/object".to_string())),
                 }]
             );
         }
@@ -63,20 +79,28 @@ mod parser {
                 Lexeme::EndToken,
             ];
             let mut context = ParseContext::new();
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
             assert_eq!(
                 triples,
                 vec![
                     Triple {
-                        subject: Iri("http://example.org/subject".to_string()),
-                        predicate: Iri("http://example.org/predicate".to_string()),
-                        object: Object::Iri("http://example.org/object1".to_string()),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/subject".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/predicate".to_string()),
+                        object: Term::NamedNode(NamedNode(
+                            "http://example.org/object1".to_string()
+                        )),
                     },
                     Triple {
-                        subject: Iri("http://example.org/subject".to_string()),
-                        predicate: Iri("http://example.org/predicate".to_string()),
-                        object: Object::Iri("http://example.org/object2".to_string()),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/subject".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/predicate".to_string()),
+                        object: Term::NamedNode(NamedNode(
+                            "http://example.org/object2".to_string()
+                        )),
                     },
                 ]
             );
@@ -93,20 +117,30 @@ mod parser {
                 Lexeme::EndToken,
             ];
             let mut context = ParseContext::new();
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
             assert_eq!(
                 triples,
                 vec![
                     Triple {
-                        subject: Iri("http://example.org/subject".to_string()),
-                        predicate: Iri("http://example.org/predicate".to_string()),
-                        object: Object::LangLiteral("Spiderman".to_string(), "en".to_string()),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/subject".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/predicate".to_string()),
+                        object: Term::Literal(Literal::new_language_tagged(
+                            "Spiderman".to_string(),
+                            "en".to_string()
+                        )),
                     },
                     Triple {
-                        subject: Iri("http://example.org/subject".to_string()),
-                        predicate: Iri("http://example.org/predicate".to_string()),
-                        object: Object::LangLiteral("Человек-паук".to_string(), "ru".to_string()),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/subject".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/predicate".to_string()),
+                        object: Term::Literal(Literal::new_language_tagged(
+                            "Человек-паук".to_string(),
+                            "ru".to_string()
+                        )),
                     },
                 ]
             );
@@ -121,23 +155,22 @@ mod parser {
             fn parse_absolute_iri() {
                 let context = ParseContext::new();
 
-                let result =
-                    parse_iri(&Lexeme::Iri("http://example.org/foo".to_string()), &context);
+                let result = parse_iri(
+                    &Token::from(Lexeme::Iri("http://example.org/foo".to_string())),
+                    &context,
+                );
 
-                assert_eq!(result, Some(Iri("http://example.org/foo".to_string())));
+                assert_eq!(result, Ok(NamedNode("http://example.org/foo".to_string())));
             }
 
             #[test]
             fn parse_relative_iri() {
-                let context = ParseContext {
-                    base: Some(String::from("http://example.org/")),
-                    prefixes: HashMap::new(),
-                    subject: None,
-                };
+                let mut context = ParseContext::new();
+                context.base = Some(String::from("http://example.org/"));
 
-                let result = parse_iri(&Lexeme::Iri("foo".to_string()), &context);
+                let result = parse_iri(&Token::from(Lexeme::Iri("foo".to_string())), &context);
 
-                assert_eq!(result, Some(Iri("http://example.org/foo".to_string())));
+                assert_eq!(result, Ok(NamedNode("http://example.org/foo".to_string())));
             }
         }
     }
@@ -147,18 +180,16 @@ mod parser {
 
         #[test]
         fn parse_prefixed_uri() {
-            let context = ParseContext {
-                base: None,
-                prefixes: HashMap::from([(
-                    String::from("foo:"),
-                    String::from("http://example.org/"),
-                )]),
-                subject: None,
-            };
+            let mut context = ParseContext::new();
+            context.prefixes =
+                HashMap::from([(String::from("foo:"), String::from("http://example.org/"))]);
 
-            let result = parse_iri(&Lexeme::PrefixedIri("foo:bar".to_string()), &context);
+            let result = parse_iri(
+                &Token::from(Lexeme::PrefixedIri("foo:bar".to_string())),
+                &context,
+            );
 
-            assert_eq!(result, Some(Iri("http://example.org/bar".to_string())));
+            assert_eq!(result, Ok(NamedNode("http://example.org/bar".to_string())));
         }
 
         #[test]
@@ -182,20 +213,24 @@ mod parser {
             ];
             let mut context = ParseContext::new();
 
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(&lexemes), &mut context).unwrap();
 
             assert_eq!(
                 triples,
                 vec![
                     Triple {
-                        subject: Iri("http://example.org/subject".to_string()),
-                        predicate: Iri("http://example.org/predicate".to_string()),
-                        object: Object::Iri("http://example.org/object".to_string()),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/subject".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/predicate".to_string()),
+                        object: Term::NamedNode(NamedNode("http://example.org/object".to_string())),
                     },
                     Triple {
-                        subject: Iri("http://example.org/subject".to_string()),
-                        predicate: Iri("http://xmlns.com/foaf/0.1/name".to_string()),
-                        object: Object::Literal("Alice".to_string()),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/subject".to_string()
+                        )),
+                        predicate: NamedNode("http://xmlns.com/foaf/0.1/name".to_string()),
+                        object: Term::Literal(Literal::new_simple("Alice".to_string())),
                     },
                 ]
             );
@@ -203,24 +238,92 @@ mod parser {
 
         #[test]
         fn parse_prefixed_uri_simple_prefix() {
-            let context = ParseContext {
-                base: None,
-                prefixes: HashMap::from([(String::from(":"), String::from("http://example.org/"))]),
-                subject: None,
-            };
+            let mut context = ParseContext::new();
+            context.prefixes =
+                HashMap::from([(String::from(":"), String::from("http://example.org/"))]);
 
-            let result = parse_iri(&Lexeme::PrefixedIri(":bar".to_string()), &context);
+            let result = parse_iri(
+                &Token::from(Lexeme::PrefixedIri(":bar".to_string())),
+                &context,
+            );
 
-            assert_eq!(result, Some(Iri("http://example.org/bar".to_string())));
+            assert_eq!(result, Ok(NamedNode("http://example.org/bar".to_string())));
         }
 
         #[test]
         fn parse_prefixed_uri_with_no_prefix_set() {
             let context = ParseContext::new();
 
-            let result = parse_iri(&Lexeme::PrefixedIri("foo:bar".to_string()), &context);
+            let result = parse_iri(
+                &Token::from(Lexeme::PrefixedIri("foo:bar".to_string())),
+                &context,
+            );
+
+            assert_eq!(
+                result,
+                Err(ParseError::UndefinedPrefix {
+                    prefix: "foo:".to_string(),
+                    span: Span::default(),
+                })
+            );
+        }
+        #[test]
+        fn parse_prefixed_names_with_punctuation() {
+            let input = "@prefix ex: <http://example.org/> .\nex:a:b ex:p ex:c\\,d;ex:q ex:e.";
+            let tokens = tokenize(input, 1, &mut LexerContext::new());
+            let mut context = ParseContext::new();
+
+            let triples = parse(&tokens, &mut context).unwrap();
 
-            assert_eq!(result, None);
+            assert_eq!(
+                triples,
+                vec![
+                    Triple {
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/a:b".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/p".to_string()),
+                        object: Term::NamedNode(NamedNode("http://example.org/c,d".to_string())),
+                    },
+                    Triple {
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/a:b".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/q".to_string()),
+                        object: Term::NamedNode(NamedNode("http://example.org/e".to_string())),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn parse_prefixed_datatype() {
+            let lexemes = vec![
+                Lexeme::Prefix(
+                    "xsd:".to_string(),
+                    "http://www.w3.org/2001/XMLSchema#".to_string(),
+                ),
+                Lexeme::EndToken,
+                Lexeme::Iri("http://example.org/s".to_string()),
+                Lexeme::Iri("http://example.org/p".to_string()),
+                Lexeme::PrefixedDataTypeLiteral("5".to_string(), "xsd:byte".to_string()),
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+
+            let triples = parse(&tokens(&lexemes), &mut context).unwrap();
+
+            assert_eq!(
+                triples,
+                vec![Triple {
+                    subject: Subject::NamedNode(NamedNode("http://example.org/s".to_string())),
+                    predicate: NamedNode("http://example.org/p".to_string()),
+                    object: Term::Literal(Literal::new_typed(
+                        "5".to_string(),
+                        "http://www.w3.org/2001/XMLSchema#byte".to_string()
+                    )),
+                }]
+            );
         }
     }
 
@@ -237,15 +340,17 @@ mod parser {
                 Lexeme::EndToken,
             ];
             let mut context = ParseContext::new();
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
             assert_eq!(context.base, Some("http://example.org/".to_string()));
             assert_eq!(
                 triples,
                 vec![Triple {
-                    subject: Iri("http://example.org/subject1".to_string()),
-                    predicate: Iri("http://example.org/predicate1".to_string()),
-                    object: Object::Iri("http://example.org/object1".to_string()),
+                    subject: Subject::NamedNode(NamedNode(
+                        "http://example.org/subject1".to_string()
+                    )),
+                    predicate: NamedNode("http://example.org/predicate1".to_string()),
+                    object: Term::NamedNode(NamedNode("http://example.org/object1".to_string())),
                 },]
             );
         }
@@ -265,25 +370,117 @@ mod parser {
                 Lexeme::EndToken,
             ];
             let mut context = ParseContext::new();
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
             assert_eq!(context.base, Some("http://example2.com/".to_string()));
             assert_eq!(
                 triples,
                 vec![
                     Triple {
-                        subject: Iri("http://example1.org/subject1".to_string()),
-                        predicate: Iri("http://example1.org/predicate1".to_string()),
-                        object: Object::Iri("http://example1.org/object1".to_string()),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example1.org/subject1".to_string()
+                        )),
+                        predicate: NamedNode("http://example1.org/predicate1".to_string()),
+                        object: Term::NamedNode(NamedNode(
+                            "http://example1.org/object1".to_string()
+                        )),
+                    },
+                    Triple {
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example2.com/subject2".to_string()
+                        )),
+                        predicate: NamedNode("http://example2.com/predicate2".to_string()),
+                        object: Term::NamedNode(NamedNode(
+                            "http://example2.com/object2".to_string()
+                        )),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn resolve_relative_iris() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::Base("http://example.org/a/b/file.ttl".to_string()),
+                Lexeme::Iri("#frag".to_string()),
+                Lexeme::Iri("../up".to_string()),
+                Lexeme::Iri("/abs/path".to_string()),
+                Lexeme::ObjectListToken,
+                Lexeme::Iri("?q".to_string()),
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
+
+            let subject = Subject::NamedNode(NamedNode(
+                "http://example.org/a/b/file.ttl#frag".to_string(),
+            ));
+            let predicate = NamedNode("http://example.org/a/up".to_string());
+
+            assert_eq!(
+                triples,
+                vec![
+                    Triple {
+                        subject: subject.clone(),
+                        predicate: predicate.clone(),
+                        object: Term::NamedNode(NamedNode(
+                            "http://example.org/abs/path".to_string()
+                        )),
                     },
                     Triple {
-                        subject: Iri("http://example2.com/subject2".to_string()),
-                        predicate: Iri("http://example2.com/predicate2".to_string()),
-                        object: Object::Iri("http://example2.com/object2".to_string()),
+                        subject,
+                        predicate,
+                        object: Term::NamedNode(NamedNode(
+                            "http://example.org/a/b/file.ttl?q".to_string()
+                        )),
                     },
                 ]
             );
         }
+
+        #[test]
+        fn resolve_relative_base_and_prefix() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::Base("http://example.org/a/".to_string()),
+                Lexeme::Base("b/".to_string()),
+                Lexeme::Prefix("ex:".to_string(), "../ns#".to_string()),
+                Lexeme::Base("/c/".to_string()),
+                Lexeme::PrefixedIri("ex:s".to_string()),
+                Lexeme::Iri("p".to_string()),
+                Lexeme::Iri("http://other.org/./o".to_string()),
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
+
+            assert_eq!(context.base, Some("http://example.org/c/".to_string()));
+            assert_eq!(
+                context.prefixes.get("ex:"),
+                Some(&"http://example.org/a/ns#".to_string())
+            );
+            assert_eq!(
+                triples,
+                vec![Triple {
+                    subject: Subject::NamedNode(NamedNode("http://example.org/a/ns#s".to_string())),
+                    predicate: NamedNode("http://example.org/c/p".to_string()),
+                    object: Term::NamedNode(NamedNode("http://other.org/./o".to_string())),
+                }]
+            );
+        }
+
+        #[test]
+        fn relative_base_without_base() {
+            let lexemes: &Vec<Lexeme> = &vec![Lexeme::Base("b/".to_string())];
+            let result = parse(&tokens(lexemes), &mut ParseContext::new());
+
+            assert_eq!(
+                result,
+                Err(ParseError::RelativeIriWithoutBase {
+                    iri: "b/".to_string(),
+                    span: Span::default(),
+                })
+            );
+        }
     }
 
     mod boolean {
@@ -301,14 +498,16 @@ mod parser {
                 Lexeme::Comment(" xsd:boolean".to_string()),
             ];
             let mut context = ParseContext::new();
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
             assert_eq!(
                 triples,
                 vec![Triple {
-                    subject: Iri("http://somecountry.example/census2007".to_string()),
-                    predicate: Iri("http://example.org/statsisLandlocked".to_string()),
-                    object: Object::Boolean(false),
+                    subject: Subject::NamedNode(NamedNode(
+                        "http://somecountry.example/census2007".to_string()
+                    )),
+                    predicate: NamedNode("http://example.org/statsisLandlocked".to_string()),
+                    object: typed("false", XSD_BOOLEAN),
                 },]
             );
         }
@@ -325,20 +524,73 @@ mod parser {
                 Lexeme::Comment(" xsd:boolean".to_string()),
             ];
             let mut context = ParseContext::new();
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
             assert_eq!(
                 triples,
                 vec![Triple {
-                    subject: Iri("http://somecountry.example/census2007".to_string()),
-                    predicate: Iri("http://example.org/statsisLandlocked".to_string()),
-                    object: Object::Boolean(true),
+                    subject: Subject::NamedNode(NamedNode(
+                        "http://somecountry.example/census2007".to_string()
+                    )),
+                    predicate: NamedNode("http://example.org/statsisLandlocked".to_string()),
+                    object: typed("true", XSD_BOOLEAN),
                 },]
             );
         }
     }
 
     mod numbers {
+        use super::super::*;
+
+        fn parse_objects(line: &str) -> Vec<Term> {
+            let tokens = tokenize(line, 1, &mut LexerContext::new());
+            let triples = parse(&tokens, &mut ParseContext::new()).unwrap();
+
+            triples.into_iter().map(|triple| triple.object).collect()
+        }
+
+        #[test]
+        fn keep_lexical_form_and_exact_value() {
+            let objects = parse_objects(
+                "<http://example.org/s> <http://example.org/p> 12345678901234567890, +007, 4.002602, 1.e5, 5.",
+            );
+
+            assert_eq!(
+                objects,
+                vec![
+                    typed("12345678901234567890", XSD_INTEGER),
+                    typed("+007", XSD_INTEGER),
+                    typed("4.002602", XSD_DECIMAL),
+                    typed("1.e5", XSD_DOUBLE),
+                    typed("5", XSD_INTEGER),
+                ]
+            );
+
+            let literal = |object: &Term| match object {
+                Term::Literal(literal) => literal.clone(),
+                object => panic!("expected a literal, got {:?}", object),
+            };
+            let integer = literal(&objects[0]).as_integer().unwrap();
+
+            assert_eq!(integer.to_string(), "12345678901234567890");
+            assert_eq!(integer.to_i64(), None);
+            assert_eq!(
+                literal(&objects[2]).as_decimal().unwrap().to_string(),
+                "4.002602"
+            );
+        }
+
+        #[test]
+        fn keep_doubles_out_of_f64_range() {
+            let objects =
+                parse_objects("<http://example.org/s> <http://example.org/p> 1E400, -1e-400 .");
+
+            assert_eq!(
+                objects,
+                vec![typed("1E400", XSD_DOUBLE), typed("-1e-400", XSD_DOUBLE)]
+            );
+        }
+
         mod integers {
             use super::super::super::*;
 
@@ -351,14 +603,16 @@ mod parser {
                     Lexeme::EndToken,
                 ];
                 let mut context = ParseContext::new();
-                let triples = parse(&lexemes, &mut context);
+                let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
                 assert_eq!(
                     triples,
                     vec![Triple {
-                        subject: Iri("http://somecountry.example/census2007".to_string()),
-                        predicate: Iri("http://example.org/stats/population".to_string()),
-                        object: Object::Integer(1234567890),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://somecountry.example/census2007".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/stats/population".to_string()),
+                        object: typed("1234567890", XSD_INTEGER),
                     },]
                 );
             }
@@ -372,14 +626,16 @@ mod parser {
                     Lexeme::EndToken,
                 ];
                 let mut context = ParseContext::new();
-                let triples = parse(&lexemes, &mut context);
+                let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
                 assert_eq!(
                     triples,
                     vec![Triple {
-                        subject: Iri("http://somecountry.example/census2007".to_string()),
-                        predicate: Iri("http://example.org/stats/population".to_string()),
-                        object: Object::Integer(-1234567890),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://somecountry.example/census2007".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/stats/population".to_string()),
+                        object: typed("-1234567890", XSD_INTEGER),
                     },]
                 );
             }
@@ -397,14 +653,16 @@ mod parser {
                     Lexeme::EndToken,
                 ];
                 let mut context = ParseContext::new();
-                let triples = parse(&lexemes, &mut context);
+                let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
                 assert_eq!(
                     triples,
                     vec![Triple {
-                        subject: Iri("http://somecountry.example/census2007".to_string()),
-                        predicate: Iri("http://example.org/stats/area".to_string()),
-                        object: Object::Decimal(4.002602),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://somecountry.example/census2007".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/stats/area".to_string()),
+                        object: typed("4.002602", XSD_DECIMAL),
                     },]
                 );
             }
@@ -418,14 +676,16 @@ mod parser {
                     Lexeme::EndToken,
                 ];
                 let mut context = ParseContext::new();
-                let triples = parse(&lexemes, &mut context);
+                let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
                 assert_eq!(
                     triples,
                     vec![Triple {
-                        subject: Iri("http://somecountry.example/census2007".to_string()),
-                        predicate: Iri("http://example.org/stats/area".to_string()),
-                        object: Object::Decimal(-4.002602),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://somecountry.example/census2007".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/stats/area".to_string()),
+                        object: typed("-4.002602", XSD_DECIMAL),
                     },]
                 );
             }
@@ -443,14 +703,16 @@ mod parser {
                     Lexeme::EndToken,
                 ];
                 let mut context = ParseContext::new();
-                let triples = parse(&lexemes, &mut context);
+                let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
                 assert_eq!(
                     triples,
                     vec![Triple {
-                        subject: Iri("http://somecountry.example/census2007".to_string()),
-                        predicate: Iri("http://example.org/stats/area".to_string()),
-                        object: Object::Double(0.0001663),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://somecountry.example/census2007".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/stats/area".to_string()),
+                        object: typed("1.663E-4", XSD_DOUBLE),
                     },]
                 );
             }
@@ -464,14 +726,16 @@ mod parser {
                     Lexeme::EndToken,
                 ];
                 let mut context = ParseContext::new();
-                let triples = parse(&lexemes, &mut context);
+                let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
                 assert_eq!(
                     triples,
                     vec![Triple {
-                        subject: Iri("http://somecountry.example/census2007".to_string()),
-                        predicate: Iri("http://example.org/stats/area".to_string()),
-                        object: Object::Double(-0.0001663),
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://somecountry.example/census2007".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/stats/area".to_string()),
+                        object: typed("-1.663E-4", XSD_DOUBLE),
                     },]
                 );
             }
@@ -494,27 +758,1044 @@ mod parser {
                 Lexeme::EndToken,
             ];
             let mut context = ParseContext::new();
-            let triples = parse(&lexemes, &mut context);
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
 
             assert_eq!(
                 triples,
                 vec![
                     Triple {
-                        subject: Iri("http://somecountry.example/census2007".to_string()),
-                        predicate: Iri("http://example.org/stats/gravity".to_string()),
-                        object: Object::MultilineLiteral(
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://somecountry.example/census2007".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/stats/gravity".to_string()),
+                        object: Term::Literal(Literal::new_simple(
                             "hello multi\n\n line \"\" literal".to_string()
-                        ),
+                        )),
                     },
                     Triple {
-                        subject: Iri("http://somecountry.example/census2007".to_string()),
-                        predicate: Iri("http://example.org/stats/gravity".to_string()),
-                        object: Object::MultilineLiteral(
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://somecountry.example/census2007".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/stats/gravity".to_string()),
+                        object: Term::Literal(Literal::new_simple(
                             "hello multi\n\n line 2\"\" literal".to_string()
-                        ),
+                        )),
+                    },
+                ]
+            );
+        }
+    }
+    mod blank_nodes {
+        use super::super::*;
+
+        #[test]
+        fn parse_blank_node_subject_and_object() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::BlankNodeLabel("alice".to_string()),
+                Lexeme::Iri("http://xmlns.com/foaf/0.1/knows".to_string()),
+                Lexeme::BlankNodeLabel("bob".to_string()),
+                Lexeme::EndToken,
+                Lexeme::BlankNodeLabel("bob".to_string()),
+                Lexeme::Iri("http://xmlns.com/foaf/0.1/knows".to_string()),
+                Lexeme::BlankNodeLabel("alice".to_string()),
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
+
+            let alice = Subject::from(context.blank_node("alice"));
+            let bob = Subject::from(context.blank_node("bob"));
+
+            assert_ne!(alice, bob);
+            assert_eq!(
+                triples,
+                vec![
+                    Triple {
+                        subject: alice.clone(),
+                        predicate: NamedNode("http://xmlns.com/foaf/0.1/knows".to_string()),
+                        object: Term::from(bob.clone()),
+                    },
+                    Triple {
+                        subject: bob,
+                        predicate: NamedNode("http://xmlns.com/foaf/0.1/knows".to_string()),
+                        object: Term::from(alice),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn blank_node_labels_are_scoped_per_document() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::BlankNodeLabel("b0".to_string()),
+                Lexeme::Iri("http://example.org/predicate".to_string()),
+                Lexeme::Iri("http://example.org/object".to_string()),
+                Lexeme::EndToken,
+            ];
+
+            let first = parse(&tokens(lexemes), &mut ParseContext::new()).unwrap();
+            let second = parse(&tokens(lexemes), &mut ParseContext::new()).unwrap();
+
+            assert_eq!(first.len(), 1);
+            assert_eq!(second.len(), 1);
+            assert_ne!(first[0].subject, second[0].subject);
+        }
+    }
+    mod blank_node_property_lists {
+        use super::super::*;
+
+        fn knows() -> NamedNode {
+            NamedNode("http://xmlns.com/foaf/0.1/knows".to_string())
+        }
+
+        fn name() -> NamedNode {
+            NamedNode("http://xmlns.com/foaf/0.1/name".to_string())
+        }
+
+        #[test]
+        fn parse_nested_property_lists_as_object() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::Iri("http://example.org/alice".to_string()),
+                Lexeme::Iri(knows().0),
+                Lexeme::BlankNodeStartToken,
+                Lexeme::Iri(name().0),
+                Lexeme::Literal("Bob".to_string()),
+                Lexeme::PredicateListToken,
+                Lexeme::Iri(knows().0),
+                Lexeme::BlankNodeStartToken,
+                Lexeme::Iri(name().0),
+                Lexeme::Literal("Eve".to_string()),
+                Lexeme::BlankNodeEndToken,
+                Lexeme::BlankNodeEndToken,
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
+
+            let bob = match &triples[3].object {
+                Term::BlankNode(BlankNode(id)) => Subject::BlankNode(BlankNode(id.clone())),
+                object => panic!("expected blank node, got {:?}", object),
+            };
+            let eve = match &triples[2].object {
+                Term::BlankNode(BlankNode(id)) => Subject::BlankNode(BlankNode(id.clone())),
+                object => panic!("expected blank node, got {:?}", object),
+            };
+
+            assert_ne!(bob, eve);
+            assert_eq!(
+                triples,
+                vec![
+                    Triple {
+                        subject: bob.clone(),
+                        predicate: name(),
+                        object: Term::Literal(Literal::new_simple("Bob".to_string())),
+                    },
+                    Triple {
+                        subject: eve.clone(),
+                        predicate: name(),
+                        object: Term::Literal(Literal::new_simple("Eve".to_string())),
+                    },
+                    Triple {
+                        subject: bob.clone(),
+                        predicate: knows(),
+                        object: Term::from(eve),
+                    },
+                    Triple {
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/alice".to_string()
+                        )),
+                        predicate: knows(),
+                        object: Term::from(bob),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn parse_property_list_as_subject() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::BlankNodeStartToken,
+                Lexeme::Iri(name().0),
+                Lexeme::Literal("Bob".to_string()),
+                Lexeme::BlankNodeEndToken,
+                Lexeme::Iri(knows().0),
+                Lexeme::BlankNodeStartToken,
+                Lexeme::BlankNodeEndToken,
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
+
+            assert_eq!(triples.len(), 2);
+            assert_eq!(triples[0].predicate, name());
+            assert_eq!(triples[1].predicate, knows());
+            assert_eq!(triples[0].subject, triples[1].subject);
+            assert!(matches!(
+                triples[1].subject,
+                Subject::BlankNode(BlankNode(_))
+            ));
+            assert!(matches!(triples[1].object, Term::BlankNode(BlankNode(_))));
+            assert_ne!(Term::from(triples[1].subject.clone()), triples[1].object);
+        }
+
+        #[test]
+        fn parse_property_list_across_lines() {
+            let mut context = ParseContext::new();
+            let mut triples = parse(
+                &tokens(&[
+                    Lexeme::Iri("http://example.org/alice".to_string()),
+                    Lexeme::Iri(knows().0),
+                    Lexeme::BlankNodeStartToken,
+                ]),
+                &mut context,
+            )
+            .unwrap();
+            triples.extend(
+                parse(
+                    &tokens(&[
+                        Lexeme::Iri(name().0),
+                        Lexeme::Literal("Bob".to_string()),
+                        Lexeme::BlankNodeEndToken,
+                        Lexeme::EndToken,
+                    ]),
+                    &mut context,
+                )
+                .unwrap(),
+            );
+
+            assert_eq!(triples.len(), 2);
+            assert_eq!(triples[0].predicate, name());
+            assert_eq!(Term::from(triples[0].subject.clone()), triples[1].object);
+        }
+    }
+    mod collections {
+        use super::super::*;
+
+        const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+
+        fn rdf(name: &str) -> NamedNode {
+            NamedNode(format!("{}{}", RDF, name))
+        }
+
+        fn subject_of(object: &Term) -> Subject {
+            Subject::try_from(object.clone()).expect("expected iri or blank node")
+        }
+
+        #[test]
+        fn parse_collection_as_object() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::Iri("http://example.org/list".to_string()),
+                Lexeme::Iri("http://example.org/items".to_string()),
+                Lexeme::CollectionStartToken,
+                Lexeme::Iri("http://example.org/a".to_string()),
+                Lexeme::Literal("b".to_string()),
+                Lexeme::CollectionEndToken,
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
+
+            let first = subject_of(&triples[4].object);
+            let second = subject_of(&triples[1].object);
+
+            assert_eq!(
+                triples,
+                vec![
+                    Triple {
+                        subject: first.clone(),
+                        predicate: rdf("first"),
+                        object: Term::NamedNode(NamedNode("http://example.org/a".to_string())),
+                    },
+                    Triple {
+                        subject: first.clone(),
+                        predicate: rdf("rest"),
+                        object: Term::from(second.clone()),
+                    },
+                    Triple {
+                        subject: second.clone(),
+                        predicate: rdf("first"),
+                        object: Term::Literal(Literal::new_simple("b".to_string())),
+                    },
+                    Triple {
+                        subject: second,
+                        predicate: rdf("rest"),
+                        object: Term::from(rdf("nil")),
+                    },
+                    Triple {
+                        subject: Subject::NamedNode(NamedNode(
+                            "http://example.org/list".to_string()
+                        )),
+                        predicate: NamedNode("http://example.org/items".to_string()),
+                        object: Term::from(first),
                     },
                 ]
             );
         }
+
+        #[test]
+        fn parse_empty_collection() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::Iri("http://example.org/list".to_string()),
+                Lexeme::Iri("http://example.org/items".to_string()),
+                Lexeme::CollectionStartToken,
+                Lexeme::CollectionEndToken,
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
+
+            assert_eq!(
+                triples,
+                vec![Triple {
+                    subject: Subject::NamedNode(NamedNode("http://example.org/list".to_string())),
+                    predicate: NamedNode("http://example.org/items".to_string()),
+                    object: Term::from(rdf("nil")),
+                }]
+            );
+        }
+
+        #[test]
+        fn parse_collection_as_subject() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::CollectionStartToken,
+                Lexeme::Unknown("1".to_string()),
+                Lexeme::CollectionEndToken,
+                Lexeme::Iri("http://example.org/p".to_string()),
+                Lexeme::Iri("http://example.org/o".to_string()),
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
+
+            assert_eq!(triples.len(), 3);
+            assert_eq!(triples[0].predicate, rdf("first"));
+            assert_eq!(triples[0].object, typed("1", XSD_INTEGER));
+            assert_eq!(triples[1].object, Term::from(rdf("nil")));
+            assert_eq!(triples[2].subject, triples[0].subject);
+            assert_eq!(
+                triples[2].predicate,
+                NamedNode("http://example.org/p".to_string())
+            );
+        }
+
+        #[test]
+        fn parse_nested_collections_in_property_list() {
+            let lexemes: &Vec<Lexeme> = &vec![
+                Lexeme::BlankNodeStartToken,
+                Lexeme::Iri("http://example.org/items".to_string()),
+                Lexeme::CollectionStartToken,
+                Lexeme::CollectionStartToken,
+                Lexeme::Iri("http://example.org/a".to_string()),
+                Lexeme::CollectionEndToken,
+                Lexeme::BlankNodeStartToken,
+                Lexeme::Iri("http://example.org/p".to_string()),
+                Lexeme::Iri("http://example.org/o".to_string()),
+                Lexeme::BlankNodeEndToken,
+                Lexeme::CollectionStartToken,
+                Lexeme::CollectionEndToken,
+                Lexeme::CollectionEndToken,
+                Lexeme::BlankNodeEndToken,
+                Lexeme::EndToken,
+            ];
+            let mut context = ParseContext::new();
+            let triples = parse(&tokens(lexemes), &mut context).unwrap();
+
+            let firsts: Vec<&Triple> = triples
+                .iter()
+                .filter(|triple| triple.predicate == rdf("first"))
+                .collect();
+            let rests: Vec<&Triple> = triples
+                .iter()
+                .filter(|triple| triple.predicate == rdf("rest"))
+                .collect();
+
+            // inner list: (a), outer list: ((a) [ p o ] ())
+            assert_eq!(firsts.len(), 4);
+            assert_eq!(rests.len(), 4);
+            assert_eq!(firsts[3].object, Term::from(rdf("nil")));
+            assert!(matches!(firsts[2].object, Term::BlankNode(BlankNode(_))));
+            assert!(triples.iter().any(|triple| {
+                Term::from(triple.subject.clone()) == firsts[2].object
+                    && triple.predicate == NamedNode("http://example.org/p".to_string())
+            }));
+
+            let property_list = triples.last().unwrap();
+
+            assert_eq!(
+                property_list.predicate,
+                NamedNode("http://example.org/items".to_string())
+            );
+            assert_eq!(property_list.object, Term::from(firsts[1].subject.clone()));
+        }
+    }
+    mod errors {
+        use super::super::*;
+
+        fn parse_line(line: &str) -> Result<Vec<Triple>, ParseError> {
+            let tokens = tokenize(line, 3, &mut LexerContext::new());
+
+            parse(&tokens, &mut ParseContext::new())
+        }
+
+        #[test]
+        fn undefined_prefix() {
+            let result = parse_line("ex:subject ex:predicate ex:object .");
+
+            assert_eq!(
+                result,
+                Err(ParseError::UndefinedPrefix {
+                    prefix: "ex:".to_string(),
+                    span: Span {
+                        line: 3,
+                        start: 0,
+                        end: 10,
+                        offset: 0,
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn undefined_datatype_prefix() {
+            let result =
+                parse_line("<http://example.org/s> <http://example.org/p> \"5\"^^xsd:integer .");
+
+            assert_eq!(
+                result,
+                Err(ParseError::UndefinedPrefix {
+                    prefix: "xsd:".to_string(),
+                    span: Span {
+                        line: 3,
+                        start: 46,
+                        end: 62,
+                        offset: 46,
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn relative_iri_without_base() {
+            let result = parse_line(
+                "<http://example.org/subject> <predicate> <http://example.org/object> .",
+            );
+
+            assert_eq!(
+                result,
+                Err(ParseError::RelativeIriWithoutBase {
+                    iri: "predicate".to_string(),
+                    span: Span {
+                        line: 3,
+                        start: 29,
+                        end: 40,
+                        offset: 29,
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn unexpected_token() {
+            let result = parse_line(
+                "<http://example.org/s> <http://example.org/p> <http://example.org/o> \"no comma\" .",
+            );
+
+            assert_eq!(
+                result,
+                Err(ParseError::UnexpectedToken {
+                    token: "\"no comma\"".to_string(),
+                    span: Span {
+                        line: 3,
+                        start: 69,
+                        end: 79,
+                        offset: 69,
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn literal_as_subject() {
+            let result = parse_line("\"subject\" <http://example.org/p> <http://example.org/o> .");
+
+            assert!(matches!(result, Err(ParseError::UnexpectedToken { .. })));
+        }
+
+        #[test]
+        fn incomplete_statements() {
+            let inputs = [
+                ("<http://ex/s> <http://ex/p> .", 28),
+                ("<http://ex/s> .", 14),
+                ("<http://ex/s> <http://ex/p> <http://ex/o> , .", 44),
+                (
+                    "<http://ex/s> <http://ex/p> ; <http://ex/q> <http://ex/o> .",
+                    28,
+                ),
+                ("<http://ex/s> ; <http://ex/q> <http://ex/o> .", 14),
+                ("<http://ex/s> <http://ex/p> [ <http://ex/q> ] .", 44),
+                ("[] .", 3),
+            ];
+
+            for (input, column) in inputs {
+                let error = parse_line(input).unwrap_err();
+
+                assert!(
+                    matches!(error, ParseError::UnexpectedToken { .. }),
+                    "{}",
+                    input
+                );
+                assert_eq!(error.column(), column, "{}", input);
+            }
+
+            // a `;` may follow an object and may be repeated
+            let complete = [
+                "<http://ex/s> <http://ex/p> <http://ex/o> ; .",
+                "<http://ex/s> <http://ex/p> <http://ex/o> ; ; <http://ex/q> <http://ex/o> .",
+                "[ <http://ex/p> <http://ex/o> ] .",
+                "[ <http://ex/p> <http://ex/o> ; ] <http://ex/q> [] .",
+            ];
+
+            for input in complete {
+                assert!(parse_line(input).is_ok(), "{}", input);
+            }
+        }
+
+        #[test]
+        fn unbalanced_brackets() {
+            let result = parse_line("<http://example.org/s> <http://example.org/p> [ .");
+
+            assert_eq!(result.unwrap_err().column(), 48);
+        }
+
+        #[test]
+        fn unterminated_literal() {
+            let result = parse_line("<http://example.org/s> <http://example.org/p> \"open .");
+
+            assert_eq!(
+                result,
+                Err(ParseError::UnterminatedLiteral {
+                    literal: "\"open .".to_string(),
+                    span: Span {
+                        line: 3,
+                        start: 46,
+                        end: 53,
+                        offset: 46,
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn broken_literal_suffix() {
+            for (input, token) in [
+                ("<http://ex/s> <http://ex/p> \"x\"@ .", "\"x\"@"),
+                ("<http://ex/s> <http://ex/p> \"true\"@ .", "\"true\"@"),
+                ("<http://ex/s> <http://ex/p> \"1\"^^ .", "\"1\"^^"),
+            ] {
+                assert_eq!(
+                    parse_line(input),
+                    Err(ParseError::UnexpectedToken {
+                        token: token.to_string(),
+                        span: Span {
+                            line: 3,
+                            start: 28,
+                            end: 28 + token.len(),
+                            offset: 28,
+                        },
+                    })
+                );
+            }
+        }
+
+        #[test]
+        fn invalid_escape_in_literal() {
+            let input = r#"<http://ex/s> <http://ex/p> "bad\uD800" .
+<http://ex/s> <http://ex/p> """long\q
+text""" ."#;
+            let mut lexer_context = LexerContext::new();
+            let tokens: Vec<Token> = input
+                .lines()
+                .enumerate()
+                .flat_map(|(i, line)| tokenize(line, i as u32 + 1, &mut lexer_context))
+                .collect();
+            let mut context = ParseContext::new();
+
+            assert_eq!(parse_collecting(&tokens, &mut context), vec![]);
+            assert_eq!(lexer_context.diagnostics.len(), 2);
+            assert_eq!(context.diagnostics.len(), 2);
+        }
+
+        #[test]
+        fn invalid_escape_in_iri() {
+            for input in [
+                r"<http://example.org/s> <http://example.org/p> <http://example.org/\n> .",
+                r"<http://example.org/s> <http://example.org/p> <http://example.org/a\uD800b> .",
+                r#"<http://example.org/s> <http://example.org/p> "x"^^<http://example.org/\q> ."#,
+            ] {
+                let results: Vec<Result<Triple, ParseError>> = TurtleParser::from(input).collect();
+
+                assert!(!results.is_empty(), "{}", input);
+                assert!(results.iter().all(Result::is_err), "{}", input);
+            }
+        }
+
+        #[test]
+        fn error_position_after_multibyte_characters() {
+            let result =
+                parse_line("<http://example.org/s> <http://example.org/p> \"паук\" ex:o .");
+            let span = result.unwrap_err().span();
+
+            assert_eq!((span.line, span.start, span.end), (3, 53, 57));
+            assert_eq!(span.offset, 57);
+        }
+    }
+    mod diagnostics {
+        use super::super::*;
+
+        #[test]
+        fn collect_errors_and_continue_after_broken_statement() {
+            let input = [
+                "@prefix ex: <http://example.org/> .",
+                "ex:a foo:b ex:c ; ex:d ex:e .",
+                "ex:a ex:b ex:c",
+                "  ex:d .",
+                "ex:a ex:b ex:c .",
+            ];
+            let mut lexer_context = LexerContext::new();
+            let mut context = ParseContext::new();
+            let mut triples = vec![];
+
+            for (i, line) in (1..).zip(input) {
+                let tokens = tokenize(line, i, &mut lexer_context);
+
+                triples.extend(parse_collecting(&tokens, &mut context));
+            }
+
+            assert_eq!(
+                triples,
+                vec![Triple {
+                    subject: Subject::NamedNode(NamedNode("http://example.org/a".to_string())),
+                    predicate: NamedNode("http://example.org/b".to_string()),
+                    object: Term::NamedNode(NamedNode("http://example.org/c".to_string())),
+                }]
+            );
+            assert_eq!(
+                context.diagnostics,
+                vec![
+                    Diagnostic::error(
+                        "undefined prefix `foo:`",
+                        Span {
+                            line: 2,
+                            start: 5,
+                            end: 10,
+                            offset: 5,
+                        },
+                    ),
+                    Diagnostic::error(
+                        "unexpected token `ex:d`",
+                        Span {
+                            line: 4,
+                            start: 2,
+                            end: 6,
+                            offset: 2,
+                        },
+                    ),
+                ]
+            );
+        }
+    }
+
+    mod turtle_parser {
+        use super::super::*;
+        use std::io::{self, BufReader, Read};
+
+        // a reader that fails once the given input is consumed
+        struct FailingReader<'a>(&'a [u8]);
+
+        impl Read for FailingReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                match self.0.is_empty() {
+                    true => Err(io::Error::other("disk on fire")),
+                    false => self.0.read(buf),
+                }
+            }
+        }
+
+        fn triple(subject: &str, object: Term) -> Triple {
+            Triple {
+                subject: Subject::NamedNode(NamedNode(format!("http://example.org/{}", subject))),
+                predicate: NamedNode("http://example.org/p".to_string()),
+                object,
+            }
+        }
+
+        #[test]
+        fn iterate_triples() {
+            let input = "@prefix ex: <http://example.org/> .
+ex:a ex:p \"one\", 2 ;
+    ex:p ex:b .
+ex:b ex:p \"\"\"multi
+line\"\"\" .";
+            let triples: Result<Vec<Triple>, ParseError> = TurtleParser::from(input).collect();
+
+            assert_eq!(
+                triples,
+                Ok(vec![
+                    triple("a", Term::Literal(Literal::new_simple("one".to_string()))),
+                    triple("a", typed("2", XSD_INTEGER)),
+                    triple(
+                        "a",
+                        Term::NamedNode(NamedNode("http://example.org/b".to_string()))
+                    ),
+                    triple(
+                        "b",
+                        Term::Literal(Literal::new_simple("multi\nline".to_string()))
+                    ),
+                ])
+            );
+        }
+
+        #[test]
+        fn yield_triples_before_the_input_is_read() {
+            let input = "@prefix ex: <http://example.org/> .\nex:a ex:p ex:b .\n";
+            let reader = BufReader::new(FailingReader(input.as_bytes()));
+            let mut parser = TurtleParser::new(reader);
+
+            assert_eq!(
+                parser.next(),
+                Some(Ok(triple(
+                    "a",
+                    Term::NamedNode(NamedNode("http://example.org/b".to_string()))
+                )))
+            );
+            assert_eq!(
+                parser.context().prefixes.get("ex:"),
+                Some(&"http://example.org/".to_string())
+            );
+            assert!(matches!(parser.next(), Some(Err(ParseError::Io { .. }))));
+            assert_eq!(parser.next(), None);
+        }
+
+        #[test]
+        fn continue_after_errors() {
+            let input = "@prefix ex: <http://example.org/> .
+ex:a foo:p ex:b .
+ex:a ex:p <http://unterminated .
+ex:b ex:p 1 .";
+            let results: Vec<Result<Triple, ParseError>> = TurtleParser::from(input).collect();
+
+            assert_eq!(
+                results,
+                vec![
+                    Err(ParseError::UndefinedPrefix {
+                        prefix: "foo:".to_string(),
+                        span: Span {
+                            line: 2,
+                            start: 5,
+                            end: 10,
+                            offset: 41,
+                        },
+                    }),
+                    Err(ParseError::InvalidSyntax {
+                        message: "unterminated iri, missing `>`".to_string(),
+                        span: Span {
+                            line: 3,
+                            start: 10,
+                            end: 32,
+                            offset: 64,
+                        },
+                    }),
+                    Err(ParseError::UnexpectedToken {
+                        token: "ex:p".to_string(),
+                        span: Span {
+                            line: 4,
+                            start: 5,
+                            end: 9,
+                            offset: 92,
+                        },
+                    }),
+                ]
+            );
+        }
+
+        #[test]
+        fn unexpected_end_of_input() {
+            let input = "<http://example.org/a> <http://example.org/p> 1 .\n<http://example.org/a> <http://example.org/p> [ <http://example.org/p> 2";
+            let results: Vec<Result<Triple, ParseError>> = TurtleParser::from(input).collect();
+
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[0], Ok(triple("a", typed("1", XSD_INTEGER))));
+            assert_eq!(
+                results[1].as_ref().map_err(|error| error.message()),
+                Err("unexpected end of input, statement is missing `.`".to_string())
+            );
+            assert_eq!(results[1].as_ref().unwrap_err().line(), 2);
+        }
+
+        #[test]
+        fn parse_from_buf_read() {
+            let input = "<http://example.org/a> <http://example.org/p> true .\n".repeat(1000);
+            let reader = BufReader::with_capacity(16, input.as_bytes());
+
+            assert_eq!(
+                TurtleParser::new(reader)
+                    .filter(|result| result.is_ok())
+                    .count(),
+                1000
+            );
+        }
+    }
+
+    mod sinks {
+        use super::super::*;
+
+        // records events instead of triples, like a loader or writer would
+        #[derive(Default)]
+        struct Recorder {
+            events: Vec<String>,
+            count: usize,
+        }
+
+        impl TripleSink for Recorder {
+            fn triple(&mut self, triple: Triple) {
+                self.count += 1;
+                self.events.push(format!("triple {}", triple.object));
+            }
+
+            fn prefix(&mut self, name: &str, iri: &str) {
+                self.events.push(format!("prefix {} {}", name, iri));
+            }
+
+            fn base(&mut self, iri: &str) {
+                self.events.push(format!("base {}", iri));
+            }
+        }
+
+        // only counts, prefix and base use the default implementations
+        struct Counter(usize);
+
+        impl TripleSink for Counter {
+            fn triple(&mut self, _triple: Triple) {
+                self.0 += 1;
+            }
+        }
+
+        const INPUT: &str = "@base <http://example.org/> .
+@prefix ex: <http://example.org/ns#> .
+ex:a ex:p 1, 2 .
+@prefix ex: <http://example.org/other#> .
+ex:a ex:p ( 3 ) .";
+
+        #[test]
+        fn drive_sink_in_document_order() {
+            let mut sink = Recorder::default();
+
+            parse_reader_into(INPUT.as_bytes(), &mut ParseContext::new(), &mut sink).unwrap();
+
+            assert_eq!(sink.count, 5);
+            assert_eq!(
+                sink.events[..5],
+                [
+                    "base http://example.org/",
+                    "prefix ex: http://example.org/ns#",
+                    "triple \"1\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+                    "triple \"2\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+                    "prefix ex: http://example.org/other#",
+                ]
+            );
+        }
+
+        #[test]
+        fn parse_into_sink() {
+            let mut tokens: Vec<Token> = Vec::new();
+            let mut lexer_context = LexerContext::new();
+
+            for (i, line) in (1..).zip(INPUT.lines()) {
+                tokens.append(&mut tokenize(line, i, &mut lexer_context));
+            }
+
+            let mut counter = Counter(0);
+            let mut triples: Vec<Triple> = Vec::new();
+
+            parse_into(&tokens, &mut ParseContext::new(), &mut counter).unwrap();
+            parse_into(&tokens, &mut ParseContext::new(), &mut triples).unwrap();
+
+            assert_eq!(counter.0, 5);
+            assert_eq!(triples.len(), 5);
+        }
+
+        #[test]
+        fn stop_at_first_error() {
+            let input = "<http://example.org/a> <http://example.org/p> 1 .
+<http://example.org/a> <http://example.org/p> <http://unterminated .
+<http://example.org/a> <http://example.org/p> 2 .";
+            let mut counter = Counter(0);
+            let result =
+                parse_reader_into(input.as_bytes(), &mut ParseContext::new(), &mut counter);
+
+            assert_eq!(counter.0, 1);
+            assert_eq!(
+                result.map_err(|error| (error.line(), error.message())),
+                Err((2, "unterminated iri, missing `>`".to_string()))
+            );
+        }
+    }
+
+    mod trig {
+        use super::super::*;
+
+        fn ex(name: &str) -> NamedNode {
+            NamedNode(format!("http://example.org/{}", name))
+        }
+
+        fn quad(subject: &str, object: &str, graph_name: Option<GraphName>) -> Quad {
+            Quad {
+                subject: Subject::from(ex(subject)),
+                predicate: ex("p"),
+                object: Term::from(ex(object)),
+                graph_name,
+            }
+        }
+
+        fn messages(input: &str) -> Vec<String> {
+            TrigParser::from(input)
+                .filter_map(|result| result.err())
+                .map(|error| error.message())
+                .collect()
+        }
+
+        #[test]
+        fn parse_graphs() {
+            let input = "@prefix ex: <http://example.org/> .
+ex:a ex:p ex:b .
+GRAPH ex:g { ex:a ex:p ex:c }
+ex:h {
+    ex:a ex:p ex:d .
+    ex:b ex:p ex:e
+}
+{ ex:a ex:p ex:f . }
+graph _:g { ex:a ex:p ex:g . }";
+            let quads: Vec<Quad> = TrigParser::from(input).map(Result::unwrap).collect();
+            let g = Some(GraphName::from(ex("g")));
+            let h = Some(GraphName::from(ex("h")));
+
+            assert_eq!(
+                quads[..5],
+                [
+                    quad("a", "b", None),
+                    quad("a", "c", g),
+                    quad("a", "d", h.clone()),
+                    quad("b", "e", h),
+                    quad("a", "f", None),
+                ]
+            );
+            assert!(matches!(quads[5].graph_name, Some(GraphName::BlankNode(_))));
+        }
+
+        #[test]
+        fn anonymous_graph_labels() {
+            let input = "@prefix ex: <http://example.org/> .
+[] { ex:a ex:p ex:b }
+GRAPH [ ] { ex:a ex:p ex:c }
+[] ex:p ex:d .";
+            let quads: Vec<Quad> = TrigParser::from(input).map(Result::unwrap).collect();
+
+            assert_eq!(quads.len(), 3);
+            assert!(matches!(quads[0].graph_name, Some(GraphName::BlankNode(_))));
+            assert!(matches!(quads[1].graph_name, Some(GraphName::BlankNode(_))));
+            assert_ne!(quads[0].graph_name, quads[1].graph_name);
+            assert_eq!(quads[2].graph_name, None);
+            assert!(matches!(quads[2].subject, Subject::BlankNode(_)));
+
+            let prefix = "@prefix ex: <http://example.org/> .\n";
+
+            assert_eq!(
+                messages(&format!("{}[ ex:p ex:o ] {{ ex:a ex:p ex:b }}", prefix)),
+                ["unexpected token `{`"]
+            );
+            assert_eq!(
+                messages(&format!(
+                    "{}GRAPH [ ex:p ex:o ] {{ ex:a ex:p ex:b }}",
+                    prefix
+                )),
+                ["unexpected token `]`"]
+            );
+        }
+
+        #[test]
+        fn share_prefixes_and_blank_nodes_across_graphs() {
+            let input = "<http://example.org/g1> { @prefix ex: <http://example.org/> . }";
+
+            assert_eq!(
+                messages(input),
+                ["unexpected token `@prefix ex: <http://example.org/>`"]
+            );
+
+            let input = "@prefix ex: <http://example.org/> .
+ex:g1 { _:x ex:p ex:a }
+ex:g2 { _:x ex:p ex:b }";
+            let quads: Vec<Quad> = TrigParser::from(input).map(Result::unwrap).collect();
+
+            assert_eq!(quads.len(), 2);
+            assert_eq!(quads[0].subject, quads[1].subject);
+        }
+
+        #[test]
+        fn graph_errors() {
+            let prefix = "@prefix ex: <http://example.org/> .\n";
+
+            assert_eq!(
+                messages(&format!("{}ex:g {{ ex:h {{ ex:a ex:p ex:b }} }}", prefix)),
+                ["unexpected token `{`", "unexpected token `}`"]
+            );
+            assert_eq!(
+                messages(&format!("{}ex:g {{ ex:a ex:p ex:b .", prefix)),
+                ["unexpected end of input, graph is missing `}`"]
+            );
+            assert_eq!(
+                messages(&format!("{}GRAPH {{ ex:a ex:p ex:b }}", prefix)),
+                ["unexpected token `{`"]
+            );
+            assert_eq!(
+                messages(&format!("{}ex:g ex:p {{ ex:a ex:p ex:b }}", prefix)),
+                ["unexpected token `{`"]
+            );
+        }
+
+        #[test]
+        fn recover_at_the_end_of_a_graph() {
+            let input = "@prefix ex: <http://example.org/> .
+ex:g { ex:a ex:p \"literal\" ex:b }
+ex:a ex:p ex:c .";
+            let results: Vec<Result<Quad, ParseError>> = TrigParser::from(input).collect();
+
+            assert_eq!(results.len(), 2);
+            assert!(results[0].is_err());
+            assert_eq!(results[1], Ok(quad("a", "c", None)));
+        }
+
+        #[test]
+        fn graphs_are_not_turtle() {
+            let results: Vec<Result<Triple, ParseError>> =
+                TurtleParser::from("{ <http://example.org/a> <http://example.org/p> 1 }").collect();
+
+            assert_eq!(
+                results[0].as_ref().map_err(|error| error.message()),
+                Err("unexpected token `{`".to_string())
+            );
+        }
+
+        #[test]
+        fn collect_quads_into_vec() {
+            let input = "<http://example.org/g> { <http://example.org/a> <http://example.org/p> ( <http://example.org/b> ) }";
+            let mut quads: Vec<Quad> = Vec::new();
+
+            parse_reader_into(input.as_bytes(), &mut ParseContext::new_trig(), &mut quads).unwrap();
+
+            assert_eq!(quads.len(), 3);
+            assert!(quads
+                .iter()
+                .all(|quad| quad.graph_name == Some(GraphName::from(ex("g")))));
+        }
     }
 }
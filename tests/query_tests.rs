@@ -0,0 +1,141 @@
+use turtle_wa::graph::*;
+use turtle_wa::parser::*;
+use turtle_wa::query::*;
+use turtle_wa::term::*;
+
+const DATA: &str = "@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix ex: <http://example.org/> .
+ex:alice a foaf:Person ; foaf:name \"Alice\" ; foaf:knows ex:bob, ex:carol .
+ex:bob a foaf:Person ; foaf:name \"Bob\" ; foaf:knows ex:bob .
+ex:carol foaf:name \"Carol\" .
+ex:rex a ex:Dog ; foaf:name \"Rex\" .";
+
+// the graph and a context with its prefixes
+fn load() -> (Graph, ParseContext) {
+    let mut graph = Graph::new();
+    let mut context = ParseContext::new();
+
+    parse_reader_into(DATA.as_bytes(), &mut context, &mut graph).unwrap();
+
+    (graph, context)
+}
+
+fn query(pattern: &str) -> Vec<Vec<String>> {
+    let (graph, mut context) = load();
+    let pattern = BasicGraphPattern::parse(pattern, &mut context).unwrap();
+    let mut rows: Vec<Vec<String>> = pattern
+        .evaluate(&graph)
+        .iter()
+        .map(|bindings| bindings.values().map(Term::to_string).collect())
+        .collect();
+
+    rows.sort();
+    rows
+}
+
+#[test]
+fn join_patterns() {
+    assert_eq!(
+        query("?p a foaf:Person . ?p foaf:name ?n"),
+        [
+            ["\"Alice\"", "<http://example.org/alice>"],
+            ["\"Bob\"", "<http://example.org/bob>"],
+        ]
+    );
+    assert_eq!(
+        query("?a foaf:knows ?b . ?b foaf:name ?name ; a foaf:Person ."),
+        [
+            [
+                "<http://example.org/alice>",
+                "<http://example.org/bob>",
+                "\"Bob\""
+            ],
+            [
+                "<http://example.org/bob>",
+                "<http://example.org/bob>",
+                "\"Bob\""
+            ],
+        ]
+    );
+}
+
+#[test]
+fn repeated_variables_match_the_same_term() {
+    assert_eq!(query("?x foaf:knows ?x"), [["<http://example.org/bob>"]]);
+}
+
+#[test]
+fn constant_patterns_and_empty_results() {
+    assert_eq!(
+        query("ex:alice foaf:knows ex:carol"),
+        [Vec::<String>::new()]
+    );
+    assert!(query("ex:carol foaf:knows ex:alice").is_empty());
+    assert!(query("?p a foaf:Person . ?p foaf:name \"Rex\" .").is_empty());
+    assert_eq!(query("").len(), 1);
+}
+
+#[test]
+fn parse_patterns() {
+    let mut context = ParseContext::new();
+    let pattern = BasicGraphPattern::parse(
+        "@prefix ex: <http://example.org/> .\n?s ex:p ?o, 1 ; $p \"x\"@en .",
+        &mut context,
+    )
+    .unwrap();
+    let p = || TermPattern::Term(Term::from(NamedNode::new("http://example.org/p")));
+    let s = || TermPattern::Variable("s".to_string());
+
+    assert_eq!(pattern.variables(), ["s", "o", "p"]);
+    assert_eq!(
+        pattern.patterns,
+        [
+            TriplePattern {
+                subject: s(),
+                predicate: p(),
+                object: TermPattern::Variable("o".to_string()),
+            },
+            TriplePattern {
+                subject: s(),
+                predicate: p(),
+                object: TermPattern::Term(Term::from(Literal::new_typed("1", XSD_INTEGER))),
+            },
+            TriplePattern {
+                subject: s(),
+                predicate: TermPattern::Variable("p".to_string()),
+                object: TermPattern::Term(Term::from(Literal::new_language_tagged("x", "en"))),
+            },
+        ]
+    );
+}
+
+#[test]
+fn pattern_errors() {
+    let error = |pattern: &str| {
+        BasicGraphPattern::parse(pattern, &mut ParseContext::new())
+            .unwrap_err()
+            .message()
+    };
+
+    assert_eq!(error("?s \"p\" ?o"), "unexpected token `\"p\"`");
+    assert_eq!(error("?s _:b ?o"), "unexpected token `_:b`");
+    assert_eq!(error("\"s\" ?p ?o"), "unexpected token `\"s\"`");
+    assert_eq!(error("?s ?p ?o . 1 ?p ?o"), "unexpected token `1`");
+    assert_eq!(error("?s ex:p ?o"), "undefined prefix `ex:`");
+    assert_eq!(
+        error("?s ?p"),
+        "unexpected end of input, statement is missing `.`"
+    );
+    assert_eq!(error("?s ?p ?"), "expected variable name");
+}
+
+#[test]
+fn variables_are_not_turtle() {
+    let results: Vec<Result<Triple, ParseError>> =
+        TurtleParser::from("?s <http://example.org/p> 1 .").collect();
+
+    assert_eq!(
+        results[0].as_ref().map_err(|error| error.message()),
+        Err("unexpected token `?s`".to_string())
+    );
+}
@@ -0,0 +1,276 @@
+use turtle_wa::graph::*;
+use turtle_wa::parser::*;
+use turtle_wa::server::*;
+
+const DATA: &str = "@prefix ex: <http://example.org/> .
+ex:alice ex:name \"Alice\" ; ex:knows ex:bob .
+ex:bob ex:name \"Bob\" .";
+
+fn server() -> Server {
+    let mut graph = Graph::new();
+    let mut context = ParseContext::new();
+
+    parse_reader_into(DATA.as_bytes(), &mut context, &mut graph).unwrap();
+
+    Server::new(graph, context.prefixes)
+}
+
+fn request(text: &str) -> Request {
+    Request::read(&mut text.as_bytes()).unwrap()
+}
+
+#[test]
+fn read_requests() {
+    let request = request(
+        "POST /sparql?a=1&query=SELECT+%3Fs%20{}&flag HTTP/1.1\r\n\
+         Host: localhost\r\n\
+         Content-Type: Application/Sparql-Query; charset=utf-8\r\n\
+         Content-Length: 5\r\n\
+         \r\n\
+         hello, ignored",
+    );
+
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/sparql");
+    assert_eq!(request.parameter("query"), Some("SELECT ?s {}"));
+    assert_eq!(request.parameter("flag"), Some(""));
+    assert_eq!(
+        request.header("CONTENT-TYPE"),
+        Some("Application/Sparql-Query; charset=utf-8")
+    );
+    assert_eq!(request.body, "hello");
+
+    for invalid in [
+        "GET\r\n\r\n",
+        "GET / SPDY/3\r\n\r\n",
+        "GET / HTTP/1.1\r\nno colon\r\n\r\n",
+        "POST / HTTP/1.1\r\nContent-Length: many\r\n\r\n",
+        "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n",
+    ] {
+        let error = Request::read(&mut invalid.as_bytes()).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "{}", invalid);
+        assert_eq!(error_status(&error), 400, "{}", invalid);
+    }
+}
+
+#[test]
+fn reject_oversized_requests() {
+    let long = "a".repeat(100_000);
+    let statuses = [
+        (format!("GET /{} HTTP/1.1\r\n\r\n", long), 431),
+        (format!("GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n", long), 431),
+        (
+            format!("GET / HTTP/1.1\r\n{}\r\n", "X-Many: a\r\n".repeat(10_000)),
+            431,
+        ),
+        (
+            "PUT /data HTTP/1.1\r\nContent-Length: 100000000\r\n\r\n".to_string(),
+            413,
+        ),
+    ];
+
+    for (text, status) in statuses {
+        let error = Request::read(&mut text.as_bytes()).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error_status(&error), status);
+    }
+
+    // a content-length longer than the body isn't waited for once the connection ends
+    let text = "PUT /data HTTP/1.1\r\nContent-Length: 100\r\n\r\nab";
+    let error = Request::read(&mut text.as_bytes()).unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn content_negotiation() {
+    let offered = ["text/turtle", "application/n-triples"];
+
+    assert_eq!(negotiate(None, &offered), Some("text/turtle"));
+    assert_eq!(negotiate(Some("*/*"), &offered), Some("text/turtle"));
+    assert_eq!(
+        negotiate(Some("text/turtle;q=0.5, application/n-triples"), &offered),
+        Some("application/n-triples")
+    );
+    assert_eq!(
+        negotiate(Some("application/*, text/*;q=0.9"), &offered),
+        Some("application/n-triples")
+    );
+    assert_eq!(
+        negotiate(Some("*/*, text/turtle;q=0"), &offered),
+        Some("application/n-triples")
+    );
+    assert_eq!(negotiate(Some("text/html"), &offered), None);
+}
+
+#[test]
+fn sparql_protocol() {
+    let mut server = server();
+
+    let response = server.handle(&request(
+        "GET /sparql?query=SELECT+%3Fn+{+ex%3Aalice+ex%3Aname+%3Fn+} HTTP/1.1\r\n\r\n",
+    ));
+
+    assert_eq!(response.status, 200);
+    assert_eq!(
+        response.header("content-type"),
+        Some("application/sparql-results+json")
+    );
+    assert!(response
+        .body
+        .contains("{\"n\":{\"type\":\"literal\",\"value\":\"Alice\"}}"));
+
+    let body = "query=ASK+%7B+%3Fs+ex%3Aknows+ex%3Abob+%7D";
+    let response = server.handle(&request(&format!(
+        "POST /sparql HTTP/1.1\r\n\
+         Content-Type: application/x-www-form-urlencoded\r\n\
+         Accept: text/csv\r\n\
+         Content-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    )));
+
+    assert_eq!((response.status, response.body.as_str()), (200, "true\r\n"));
+
+    let statuses = [
+        ("GET /sparql HTTP/1.1\r\n\r\n", 400),
+        ("GET /sparql?query=SELECT HTTP/1.1\r\n\r\n", 400),
+        (
+            "GET /sparql?query=ASK{} HTTP/1.1\r\nAccept: text/html\r\n\r\n",
+            406,
+        ),
+        (
+            "POST /sparql HTTP/1.1\r\nContent-Type: text/plain\r\n\r\n",
+            415,
+        ),
+        ("DELETE /sparql HTTP/1.1\r\n\r\n", 405),
+        ("GET /other HTTP/1.1\r\n\r\n", 404),
+    ];
+
+    for (text, status) in statuses {
+        assert_eq!(server.handle(&request(text)).status, status, "{}", text);
+    }
+}
+
+#[test]
+fn graph_store_protocol() {
+    let mut server = server();
+    let put = |content_type: &str, body: &str| {
+        request(&format!(
+            "PUT /data?graph=http%3A%2F%2Fexample.org%2Fg HTTP/1.1\r\n\
+             Content-Type: {}\r\n\
+             Content-Length: {}\r\n\r\n{}",
+            content_type,
+            body.len(),
+            body
+        ))
+    };
+    let get = request(
+        "GET /data?graph=http://example.org/g HTTP/1.1\r\nAccept: application/n-triples\r\n\r\n",
+    );
+
+    assert_eq!(server.handle(&get).status, 404);
+    assert_eq!(
+        server.handle(&put("text/turtle", "<a> <b> <c> .")).status,
+        201
+    );
+    assert_eq!(
+        server.handle(&get).body,
+        "<http://example.org/a> <http://example.org/b> <http://example.org/c> .\n"
+    );
+
+    assert_eq!(
+        server
+            .handle(&put(
+                "application/n-triples",
+                "<http://x/a> <http://x/b> \"c\" .\n"
+            ))
+            .status,
+        204
+    );
+    assert_eq!(
+        server.handle(&get).body,
+        "<http://x/a> <http://x/b> \"c\" .\n"
+    );
+
+    // a broken body leaves the graph alone
+    assert_eq!(
+        server.handle(&put("text/turtle", "<a> <b> <c>")).status,
+        400
+    );
+    assert_eq!(server.handle(&put("application/rdf+xml", "")).status, 415);
+    assert_eq!(
+        server.handle(&get).body,
+        "<http://x/a> <http://x/b> \"c\" .\n"
+    );
+
+    // HEAD has the headers of GET, the body is left out when it is written
+    let head = server.handle(&request(
+        "HEAD /data?graph=http://example.org/g HTTP/1.1\r\nAccept: application/n-triples\r\n\r\n",
+    ));
+    let mut written = Vec::new();
+
+    head.write_head(&mut written).unwrap();
+
+    let written = String::from_utf8(written).unwrap();
+
+    assert!(written.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(written.contains(&format!(
+        "\r\nContent-Length: {}\r\n",
+        server.handle(&get).body.len()
+    )));
+    assert!(written.ends_with("\r\n\r\n"));
+
+    let default = server.handle(&request("GET /data?default HTTP/1.1\r\n\r\n"));
+
+    assert_eq!(
+        default.header("content-type"),
+        Some("text/turtle; charset=utf-8")
+    );
+    assert!(default
+        .body
+        .starts_with("@prefix ex: <http://example.org/> .\n"));
+    assert!(default.body.contains("ex:alice ex:name \"Alice\" ;"));
+
+    let delete = request("DELETE /data?graph=http://example.org/g HTTP/1.1\r\n\r\n");
+
+    assert_eq!(server.handle(&delete).status, 204);
+    assert_eq!(server.handle(&delete).status, 404);
+    assert_eq!(
+        server.handle(&request("GET /data HTTP/1.1\r\n\r\n")).status,
+        400
+    );
+    assert_eq!(
+        server
+            .handle(&request("GET /data?graph=g HTTP/1.1\r\n\r\n"))
+            .status,
+        400
+    );
+}
+
+#[test]
+fn queries_see_graph_store_changes() {
+    let mut server = server();
+    let body = "<http://example.org/carol> <http://example.org/name> \"Carol\" .";
+    let post = format!(
+        "POST /data?default HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let count = request(
+        "GET /sparql?query=SELECT+%3Fn+{+%3Fp+ex%3Aname+%3Fn+} HTTP/1.1\r\nAccept: text/tab-separated-values\r\n\r\n",
+    );
+
+    assert_eq!(server.handle(&count).body.lines().count(), 3);
+    assert_eq!(server.handle(&request(&post)).status, 204);
+    assert_eq!(server.handle(&count).body.lines().count(), 4);
+    assert_eq!(
+        server
+            .handle(&request("DELETE /data?default HTTP/1.1\r\n\r\n"))
+            .status,
+        204
+    );
+    assert_eq!(server.handle(&count).body, "?n\n");
+}
@@ -0,0 +1,227 @@
+use turtle_wa::graph::*;
+use turtle_wa::parser::*;
+use turtle_wa::results::*;
+use turtle_wa::sparql::*;
+use turtle_wa::term::*;
+
+const DATA: &str = "@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix ex: <http://example.org/> .
+ex:alice a foaf:Person ; foaf:name \"Alice\"@en ; foaf:age 30 ; foaf:knows ex:bob .
+ex:bob a foaf:Person ; foaf:name \"Bob\" ; foaf:age 25.5 .
+ex:carol a foaf:Person ; foaf:name \"Carol\" ; foaf:mbox <mailto:carol@example.org> .
+ex:rex a ex:Dog ; foaf:name \"Rex\" .";
+
+fn run(query: &str) -> QueryResults {
+    let mut graph = Graph::new();
+    let mut context = ParseContext::new();
+
+    parse_reader_into(DATA.as_bytes(), &mut context, &mut graph).unwrap();
+    Query::parse(query, &mut context).unwrap().evaluate(&graph)
+}
+
+// the solutions as rows of the selected variables, unbound ones are empty
+fn select(query: &str) -> Vec<Vec<String>> {
+    match run(query) {
+        QueryResults::Solutions {
+            variables,
+            solutions,
+        } => solutions
+            .iter()
+            .map(|bindings| {
+                variables
+                    .iter()
+                    .map(|variable| {
+                        bindings
+                            .get(variable)
+                            .map(Term::to_string)
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect(),
+        QueryResults::Boolean(_) => panic!("expected solutions"),
+    }
+}
+
+fn ask(query: &str) -> bool {
+    match run(query) {
+        QueryResults::Boolean(value) => value,
+        QueryResults::Solutions { .. } => panic!("expected a boolean"),
+    }
+}
+
+#[test]
+fn select_and_order() {
+    assert_eq!(
+        select("SELECT ?n WHERE { ?p a foaf:Person ; foaf:name ?n } ORDER BY ?n"),
+        [["\"Alice\"@en"], ["\"Bob\""], ["\"Carol\""]]
+    );
+    assert_eq!(
+        select("select ?p ?age { ?p foaf:age ?age } order by desc(?age)"),
+        [
+            [
+                "<http://example.org/alice>",
+                "\"30\"^^<http://www.w3.org/2001/XMLSchema#integer>"
+            ],
+            [
+                "<http://example.org/bob>",
+                "\"25.5\"^^<http://www.w3.org/2001/XMLSchema#decimal>"
+            ],
+        ]
+    );
+    assert_eq!(
+        select("SELECT DISTINCT ?type { ?s a ?type } ORDER BY ?type LIMIT 1 OFFSET 1"),
+        [["<http://xmlns.com/foaf/0.1/Person>"]]
+    );
+}
+
+#[test]
+fn filters() {
+    assert_eq!(
+        select("SELECT ?p { ?p foaf:age ?age FILTER(?age >= 26 || ?age = 25.5) } ORDER BY ?p"),
+        [["<http://example.org/alice>"], ["<http://example.org/bob>"]]
+    );
+    assert_eq!(
+        select("SELECT ?n { ?p foaf:name ?n . FILTER regex(?n, \"^[a-c]\", \"i\") FILTER(!langMatches(lang(?n), \"en\")) } ORDER BY ?n"),
+        [["\"Bob\""], ["\"Carol\""]]
+    );
+    assert_eq!(
+        select("SELECT ?p { ?p ?x ?o FILTER(isIRI(?o) && !sameTerm(?x, <http://www.w3.org/1999/02/22-rdf-syntax-ns#type>) && str(?o) != \"http://example.org/bob\") }"),
+        [["<http://example.org/carol>"]]
+    );
+    // `<` without a valid iri after it is the less than operator
+    assert_eq!(
+        select("SELECT ?p { ?p foaf:age ?age FILTER(?age<26) }"),
+        [["<http://example.org/bob>"]]
+    );
+    // an unbound variable is an error, which filters the solution out
+    assert!(select("SELECT ?p { ?p a ex:Dog FILTER(?missing = 1) }").is_empty());
+}
+
+#[test]
+fn optional_and_union() {
+    assert_eq!(
+        select(
+            "SELECT ?p ?age { ?p a foaf:Person OPTIONAL { ?p foaf:age ?age FILTER(?age > 26) } } ORDER BY ?p"
+        ),
+        [
+            vec!["<http://example.org/alice>", "\"30\"^^<http://www.w3.org/2001/XMLSchema#integer>"],
+            vec!["<http://example.org/bob>", ""],
+            vec!["<http://example.org/carol>", ""],
+        ]
+    );
+    assert_eq!(
+        select("SELECT * { { ?x foaf:knows ?y } UNION { ?x foaf:mbox ?y } } ORDER BY ?x"),
+        [
+            ["<http://example.org/alice>", "<http://example.org/bob>"],
+            ["<http://example.org/carol>", "<mailto:carol@example.org>"],
+        ]
+    );
+    assert_eq!(
+        select("SELECT ?p { ?p a foaf:Person FILTER(!bound(?age)) OPTIONAL { ?p foaf:age ?age } }"),
+        [["<http://example.org/carol>"]]
+    );
+}
+
+#[test]
+fn ask_queries() {
+    assert!(ask(
+        "PREFIX e: <http://example.org/> ASK { e:alice foaf:knows e:bob }"
+    ));
+    assert!(!ask("ASK WHERE { ?p a ex:Dog ; foaf:age ?age }"));
+    assert!(ask(
+        "ASK { ?p foaf:age ?age FILTER(datatype(?age) = <http://www.w3.org/2001/XMLSchema#decimal>) }"
+    ));
+    // language tagged strings are true unless they are empty
+    assert!(ask("ASK { ex:alice foaf:name ?n FILTER(?n) }"));
+    assert!(!ask("ASK { ?p foaf:name ?n FILTER(\"\"@en) }"));
+}
+
+#[test]
+fn invalid_queries() {
+    let errors = [
+        "SELECT { ?s ?p ?o }",
+        "SELECT ?s WHERE { ?s ?p }",
+        "SELECT ?s WHERE { ?s ?p ?o",
+        "SELECT ?s WHERE { ?s ?p ?o FILTER(regex(?s)) }",
+        "SELECT ?s WHERE { ?s ?p ?o } LIMIT ten",
+        "CONSTRUCT { ?s ?p ?o } WHERE { ?s ?p ?o }",
+        "SELECT ?s WHERE { ?s undefined:p ?o }",
+    ];
+
+    for query in errors {
+        let mut context = ParseContext::new();
+
+        assert!(Query::parse(query, &mut context).is_err(), "{}", query);
+    }
+}
+
+#[test]
+fn results_formats() {
+    let results = run("SELECT ?p ?n ?age { ?p foaf:name ?n OPTIONAL { ?p foaf:age ?age } FILTER(?p != ex:carol && ?p != ex:rex) } ORDER BY ?p");
+
+    assert_eq!(
+        to_string(&results, ResultsFormat::Json),
+        "{\"head\":{\"vars\":[\"p\",\"n\",\"age\"]},\n\
+         \"results\":{\"bindings\":[\n\
+         {\"age\":{\"type\":\"literal\",\"value\":\"30\",\"datatype\":\"http://www.w3.org/2001/XMLSchema#integer\"},\
+         \"n\":{\"type\":\"literal\",\"value\":\"Alice\",\"xml:lang\":\"en\"},\
+         \"p\":{\"type\":\"uri\",\"value\":\"http://example.org/alice\"}},\n\
+         {\"age\":{\"type\":\"literal\",\"value\":\"25.5\",\"datatype\":\"http://www.w3.org/2001/XMLSchema#decimal\"},\
+         \"n\":{\"type\":\"literal\",\"value\":\"Bob\"},\
+         \"p\":{\"type\":\"uri\",\"value\":\"http://example.org/bob\"}}\n\
+         ]}}\n"
+    );
+    assert_eq!(
+        to_string(&results, ResultsFormat::Csv),
+        "p,n,age\r\nhttp://example.org/alice,Alice,30\r\nhttp://example.org/bob,Bob,25.5\r\n"
+    );
+    assert_eq!(
+        to_string(&results, ResultsFormat::Tsv),
+        "?p\t?n\t?age\n\
+         <http://example.org/alice>\t\"Alice\"@en\t\"30\"^^<http://www.w3.org/2001/XMLSchema#integer>\n\
+         <http://example.org/bob>\t\"Bob\"\t\"25.5\"^^<http://www.w3.org/2001/XMLSchema#decimal>\n"
+    );
+
+    // ?n is never bound
+    let results = run("SELECT ?n { ?p foaf:mbox ?m }");
+
+    assert_eq!(to_string(&results, ResultsFormat::Csv), "n\r\n\r\n");
+    assert_eq!(
+        to_string(&run("ASK { ?s ?p ?o }"), ResultsFormat::Json),
+        "{\"head\":{},\"boolean\":true}\n"
+    );
+    assert_eq!(
+        to_string(&run("ASK { ?s a ex:Cat }"), ResultsFormat::Tsv),
+        "false\n"
+    );
+}
+
+#[test]
+fn regex_in_linear_time() {
+    // a backtracking matcher overflows the stack on the long literal and takes ages on `(a*)*b`
+    let long = "a".repeat(200_000);
+    let graph: Graph = [Triple {
+        subject: Subject::from(NamedNode::new("http://example.org/s")),
+        predicate: NamedNode::new("http://example.org/p"),
+        object: Term::from(Literal::new_simple(long)),
+    }]
+    .into_iter()
+    .collect();
+    let mut context = ParseContext::new();
+    let query = Query::parse("ASK { ?s ?p ?o FILTER regex(?o, \"a*b\") }", &mut context).unwrap();
+
+    assert_eq!(query.evaluate(&graph), QueryResults::Boolean(false));
+
+    let regex = turtle_wa::sparql_regex::Regex::new("(a*)*b", "").unwrap();
+
+    assert!(!regex.is_match(&"a".repeat(30)));
+    assert!(regex.is_match(&("a".repeat(30) + "b")));
+    assert!(turtle_wa::sparql_regex::Regex::new("a", "z").is_err());
+    assert!(turtle_wa::sparql_regex::Regex::new("a.c", "q")
+        .unwrap()
+        .is_match("xa.cx"));
+    assert!(!turtle_wa::sparql_regex::Regex::new("a.c", "q")
+        .unwrap()
+        .is_match("abc"));
+}
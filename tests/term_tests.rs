@@ -0,0 +1,117 @@
+use turtle_wa::lexer::*;
+use turtle_wa::parser::*;
+use turtle_wa::term::*;
+
+fn parse_document(input: &str) -> Vec<Triple> {
+    let mut lexer_context = LexerContext::new();
+    let mut context = ParseContext::new();
+    let mut triples: Vec<Triple> = Vec::new();
+
+    for (line_num, line) in (1..).zip(input.lines()) {
+        let tokens = tokenize(line, line_num, &mut lexer_context);
+
+        triples.extend(parse(&tokens, &mut context).unwrap());
+    }
+
+    triples
+}
+
+fn literal(term: &Term) -> &Literal {
+    match term {
+        Term::Literal(literal) => literal,
+        term => panic!("expected a literal, got {:?}", term),
+    }
+}
+
+#[test]
+fn equal_graphs_compare_equal() {
+    let a = parse_document(
+        r#"@prefix ex: <http://example.org/> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+ex:s ex:p "a", 1, true, "x"@EN-us, ex:o ."#,
+    );
+    let b = parse_document(
+        r#"@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+<http://example.org/s> <http://example.org/p> """a""" .
+<http://example.org/s> <http://example.org/p> "1"^^xsd:integer .
+<http://example.org/s> <http://example.org/p> 'true'^^xsd:boolean .
+<http://example.org/s> <http://example.org/p> "x"@en-US .
+<http://example.org/s> <http://example.org/p> <http://example.org/o> ."#,
+    );
+
+    assert_eq!(a, b);
+    assert_eq!(a[0].object, Term::from(Literal::new_typed("a", XSD_STRING)));
+}
+
+#[test]
+fn native_values() {
+    let triples = parse_document(
+        r#"@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+<http://example.org/s> <http://example.org/p> 12345678901234567890, 4.50, 1.5E2, false, "0"^^xsd:boolean, "INF"^^xsd:double, "5"^^xsd:decimal, "x"^^xsd:integer ."#,
+    );
+    let objects: Vec<&Literal> = triples
+        .iter()
+        .map(|triple| literal(&triple.object))
+        .collect();
+
+    assert_eq!(
+        objects[0].as_integer().map(|integer| integer.to_string()),
+        Some("12345678901234567890".to_string())
+    );
+    assert_eq!(
+        objects[0].as_decimal().map(|decimal| decimal.to_string()),
+        Some("12345678901234567890.0".to_string())
+    );
+    assert_eq!(
+        objects[1].as_decimal().map(|decimal| decimal.to_string()),
+        Some("4.5".to_string())
+    );
+    assert_eq!(objects[1].as_integer(), None);
+    assert_eq!(objects[2].as_double(), Some(150.0));
+    assert_eq!(objects[3].as_bool(), Some(false));
+    assert_eq!(objects[4].as_bool(), Some(false));
+    assert_eq!(objects[5].as_double(), Some(f64::INFINITY));
+    assert!(objects[6].as_decimal().is_some());
+    assert_eq!(objects[7].as_integer(), None);
+    assert_eq!(objects[3].as_integer(), None);
+}
+
+#[test]
+fn display_as_ntriples() {
+    let triples = parse_document(r#"_:a <http://example.org/p> "say \"hi\""@EN, 5 ."#);
+
+    assert_eq!(
+        triples[0].to_string(),
+        format!(
+            r#"_:{} <http://example.org/p> "say \"hi\""@en ."#,
+            match &triples[0].subject {
+                Subject::BlankNode(blank_node) => blank_node.as_str(),
+                subject => panic!("expected a blank node, got {:?}", subject),
+            }
+        )
+    );
+    assert_eq!(
+        triples[1].object.to_string(),
+        r#""5"^^<http://www.w3.org/2001/XMLSchema#integer>"#
+    );
+}
+
+#[test]
+fn display_quads_as_n_quads() {
+    let triple = Triple {
+        subject: Subject::from(BlankNode::new("b0")),
+        predicate: NamedNode::new("http://example.org/p"),
+        object: Term::from(Literal::new_language_tagged("x", "EN")),
+    };
+    let quad = Quad::new(
+        triple.clone(),
+        Some(GraphName::from(NamedNode::new("http://example.org/g"))),
+    );
+
+    assert_eq!(
+        quad.to_string(),
+        "_:b0 <http://example.org/p> \"x\"@en <http://example.org/g> ."
+    );
+    assert_eq!(Quad::from(triple.clone()).to_string(), triple.to_string());
+    assert_eq!(Triple::from(quad), triple);
+}
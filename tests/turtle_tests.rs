@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use turtle_wa::lexer::*;
+use turtle_wa::parser::*;
+use turtle_wa::term::*;
+use turtle_wa::turtle::*;
+
+fn parse_document(input: &str) -> (Vec<Triple>, HashMap<String, String>) {
+    let mut lexer_context = LexerContext::new();
+    let mut context = ParseContext::new();
+    let mut triples: Vec<Triple> = Vec::new();
+
+    for (line_num, line) in (1..).zip(input.lines()) {
+        let tokens = tokenize(line, line_num, &mut lexer_context);
+
+        triples.extend(parse(&tokens, &mut context).unwrap());
+    }
+
+    (triples, context.prefixes)
+}
+
+fn format(input: &str) -> String {
+    let (triples, prefixes) = parse_document(input);
+
+    to_string(&triples, &prefixes)
+}
+
+// blank node labels differ between documents, so compare them by position of first use
+fn normalize(triples: &[Triple]) -> Vec<Triple> {
+    let mut labels: HashMap<String, String> = HashMap::new();
+    let mut rename = |label: &String| {
+        let next = format!("b{}", labels.len());
+
+        labels.entry(label.clone()).or_insert(next).clone()
+    };
+
+    triples
+        .iter()
+        .map(|triple| Triple {
+            subject: match &triple.subject {
+                Subject::BlankNode(BlankNode(label)) => {
+                    Subject::BlankNode(BlankNode(rename(label)))
+                }
+                subject => subject.clone(),
+            },
+            predicate: triple.predicate.clone(),
+            object: match &triple.object {
+                Term::BlankNode(BlankNode(label)) => Term::BlankNode(BlankNode(rename(label))),
+                object => object.clone(),
+            },
+        })
+        .collect()
+}
+
+fn assert_round_trip(input: &str) {
+    let (triples, prefixes) = parse_document(input);
+    let (formatted, _) = parse_document(&to_string(&triples, &prefixes));
+
+    let mut expected: Vec<String> = normalize(&triples)
+        .iter()
+        .map(|triple| format!("{:?}", triple))
+        .collect();
+    let mut actual: Vec<String> = normalize(&formatted)
+        .iter()
+        .map(|triple| format!("{:?}", triple))
+        .collect();
+
+    expected.sort();
+    actual.sort();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn group_by_subject_and_predicate() {
+    let input = r#"@prefix ex: <http://example.org/> .
+ex:a ex:name "a" .
+ex:b ex:name "b" .
+ex:a ex:knows ex:b .
+ex:a ex:name "c" .
+ex:a <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> ex:Person ."#;
+
+    assert_eq!(
+        format(input),
+        r#"@prefix ex: <http://example.org/> .
+
+ex:a a ex:Person ;
+    ex:name "a", "c" ;
+    ex:knows ex:b .
+
+ex:b ex:name "b" .
+"#
+    );
+}
+
+#[test]
+fn compact_iris() {
+    let input = r#"@prefix ex: <http://example.org/> .
+@prefix deep: <http://example.org/deep/> .
+<http://example.org/deep/a> <http://example.org/p> <http://example.org/a/b> .
+<http://other.org/a> <http://example.org/p> <http://example.org/> ."#;
+
+    assert_eq!(
+        format(input),
+        r#"@prefix deep: <http://example.org/deep/> .
+@prefix ex: <http://example.org/> .
+
+deep:a ex:p <http://example.org/a/b> .
+
+<http://other.org/a> ex:p <http://example.org/> .
+"#
+    );
+}
+
+#[test]
+fn write_literals() {
+    let input = r#"@prefix ex: <http://example.org/> .
+ex:a ex:p "say \"hi\"", "Человек-паук"@ru, "1"^^<http://www.w3.org/2001/XMLSchema#byte> .
+ex:a ex:p true, 5, -4.0, 1.663E-4 ."#;
+
+    assert_eq!(
+        format(input),
+        r#"@prefix ex: <http://example.org/> .
+
+ex:a ex:p "say \"hi\"", "Человек-паук"@ru, "1"^^<http://www.w3.org/2001/XMLSchema#byte>, true, 5, -4.0, 1.663E-4 .
+"#
+    );
+}
+
+#[test]
+fn compact_datatypes() {
+    let input = r#"@prefix ex: <http://example.org/> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+ex:a ex:p "1"^^xsd:byte, "x"^^xsd:string, "y"^^<http://other.org/type> ."#;
+
+    assert_eq!(
+        format(input),
+        r#"@prefix ex: <http://example.org/> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+ex:a ex:p "1"^^xsd:byte, "x", "y"^^<http://other.org/type> .
+"#
+    );
+}
+
+#[test]
+fn inline_blank_nodes_and_collections() {
+    let input = r#"@prefix ex: <http://example.org/> .
+ex:a ex:p [ ex:q 1 ; ex:r [ ex:s 2 ] ] .
+ex:a ex:list ( 1 ex:b ) , () .
+ex:a ex:empty [] .
+ex:a ex:shared _:s .
+ex:b ex:shared _:s ."#;
+    let (triples, prefixes) = parse_document(input);
+    let shared = match &triples.last().unwrap().object {
+        Term::BlankNode(BlankNode(label)) => label.to_string(),
+        object => panic!("expected a blank node, got {:?}", object),
+    };
+
+    assert_eq!(
+        to_string(&triples, &prefixes),
+        r#"@prefix ex: <http://example.org/> .
+
+ex:a ex:p [
+        ex:q 1 ;
+        ex:r [
+            ex:s 2
+        ]
+    ] ;
+    ex:list ( 1 ex:b ), () ;
+    ex:empty [] ;
+    ex:shared _:shared .
+
+ex:b ex:shared _:shared .
+"#
+        .replace("shared .", &format!("{} .", shared))
+    );
+}
+
+#[test]
+fn write_blank_node_cycles() {
+    let input = r#"@prefix ex: <http://example.org/> .
+_:a ex:p _:b .
+_:b ex:p _:a ."#;
+
+    assert_eq!(format(input).matches("ex:p").count(), 2);
+    assert_round_trip(input);
+}
+
+#[test]
+fn round_trip() {
+    let input = r#"@prefix ex: <http://example.org/> .
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+ex:spiderman a foaf:Person ;
+    foaf:name "Spiderman", "Человек-паук"@ru ;
+    ex:enemies ( ex:goblin [ foaf:name "Doc Ock" ] ) ;
+    ex:address [ ex:city "New York" ; ex:zip 10001 ] ;
+    ex:quote "say \"hi\"\n\u00e9 \\ back" ;
+    ex:quote "say \"hi\"\\n\\u00e9" ;
+    ex:bio """multi
+line""" .
+_:unused ex:p _:shared .
+ex:other ex:p _:shared ."#;
+
+    assert_round_trip(input);
+}
+
+#[test]
+fn round_trip_iris_that_are_not_prefixed_names() {
+    // U+00B2 is alphanumeric but not allowed in PN_LOCAL
+    let input = "@prefix ex: <http://example.org/> .
+<http://example.org/x\u{00B2}> ex:p ex:a\u{00B7}b, <http://example.org/caf\u{00E9}> .";
+
+    assert_eq!(
+        format(input),
+        "@prefix ex: <http://example.org/> .
+
+<http://example.org/x\u{00B2}> ex:p ex:a\u{00B7}b, ex:caf\u{00E9} .
+"
+    );
+    assert_round_trip(input);
+}